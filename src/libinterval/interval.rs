@@ -193,6 +193,12 @@ where
     fn up(&self) -> Bound {
         self.ub.clone()
     }
+    pub(crate) fn lower_ref(&self) -> &Bound {
+        &self.lb
+    }
+    pub(crate) fn upper_ref(&self) -> &Bound {
+        &self.ub
+    }
 }
 
 impl<Bound> Interval<Bound>
@@ -1283,6 +1289,11 @@ where
     /// The interval produced is the smallest possible interval,
     /// however, only the values 0 and 3 are possible results of this multiplication.
     ///
+    /// A negative constant swaps the bounds.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(Interval::new(1, 2) * -2, Interval::new(-4, -2));
+    /// ```
     /// This method preserves empty intervals.
     /// ```
     /// # use interval::prelude::*;
@@ -1297,7 +1308,12 @@ where
         if self.is_empty() {
             Interval::empty()
         } else {
-            Interval::new(self.lower() * other.clone(), self.upper() * other.clone())
+            let (lower, upper) = (self.lower() * other.clone(), self.upper() * other.clone());
+            if other < &Bound::zero() {
+                Interval::new(upper, lower)
+            } else {
+                Interval::new(lower, upper)
+            }
         }
     }
 }
@@ -2350,6 +2366,15 @@ mod tests {
             ),
             (empty, 3, empty, empty, empty),
             (invalid, 3, empty, empty, empty),
+            (zero, -2, (-2, -2).to_interval(), (2, 2).to_interval(), zero),
+            (
+                i1_2,
+                -2,
+                (-1, 0).to_interval(),
+                (3, 4).to_interval(),
+                (-4, -2).to_interval(),
+            ),
+            (empty, -2, empty, empty, empty),
         ];
 
         for &(x, y, r1, r2, r3) in &cases {
@@ -37,14 +37,19 @@ use serde::de::SeqAccess;
 use serde::de::Visitor;
 use serde::Deserialize;
 use serde::Serialize;
+use std::borrow::{Borrow, Cow};
+use std::cmp::{max, min, Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet};
 use std::fmt;
-use std::fmt::{Display, Error, Formatter};
-use std::iter::{IntoIterator, Peekable};
+use std::fmt::{Display, Error, Formatter, LowerHex, UpperHex};
+use std::hash::Hash;
+use std::iter::{IntoIterator, Peekable, Product, Sum};
 use std::marker::PhantomData;
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, Mul, RangeInclusive, Sub};
 use trilean::SKleene;
 
-use num_traits::{Num, Zero};
+use num_integer::Integer;
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, Num, NumCast, One, ToPrimitive, Zero};
 
 #[derive(Debug, Clone)]
 pub struct IntervalSet<Bound: Width> {
@@ -124,6 +129,94 @@ where
     }
 }
 
+/// Wrapper of an interval set whose deserialization rejects the intervals that are not already sorted and disjoint,
+/// instead of sorting and merging them. It is serialized in the same format as [`IntervalSet`].
+/// ```
+/// # use interval::prelude::*;
+/// # use interval::interval_set::StrictIntervalSet;
+/// let strict = StrictIntervalSet([(1, 2), (5, 8)].to_interval_set());
+/// assert_eq!(strict.0.interval_count(), 2);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct StrictIntervalSet<Bound: Width + Num>(pub IntervalSet<Bound>);
+
+impl<Bound> fmt::Debug for StrictIntervalSet<Bound>
+where
+    Bound: Width + Num + fmt::Debug,
+    <Bound as Width>::Output: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_tuple("StrictIntervalSet")
+            .field(&self.0)
+            .finish()
+    }
+}
+
+impl<Bound> Serialize for StrictIntervalSet<Bound>
+where
+    Bound: Width + Num + Serialize,
+    Interval<Bound>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, Bound> Deserialize<'de> for StrictIntervalSet<Bound>
+where
+    Bound: Width + Num + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct StrictIntervalSetVisitor<Bound> {
+            marker: PhantomData<fn() -> Interval<Bound>>,
+        }
+        impl<'de, Bound> Visitor<'de> for StrictIntervalSetVisitor<Bound>
+        where
+            Bound: Width + Deserialize<'de> + Num,
+        {
+            type Value = StrictIntervalSet<Bound>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("sequence of sorted and disjoint intervals")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut interval_set = IntervalSet::empty();
+                if let Some(size) = seq.size_hint() {
+                    interval_set.intervals.reserve(size);
+                }
+                while let Some(interval) = seq.next_element::<Interval<Bound>>()? {
+                    interval_set.intervals.push(interval);
+                }
+                interval_set.size = interval_set
+                    .intervals_size()
+                    .map_err(serde::de::Error::custom)?;
+                Ok(StrictIntervalSet(interval_set))
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(StrictIntervalSet(IntervalSet::empty()))
+            }
+        }
+        deserializer.deserialize_any(StrictIntervalSetVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
 impl<Bound: Width> IntervalKind for IntervalSet<Bound> {}
 
 impl<Bound: Width> Collection for IntervalSet<Bound> {
@@ -157,14 +250,124 @@ impl<'a, Bound: Width> IntoIterator for &'a mut IntervalSet<Bound> {
     }
 }
 
+impl<Bound: Width> AsRef<[Interval<Bound>]> for IntervalSet<Bound> {
+    /// Views the sorted and non-overlapping intervals of the set as a slice.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// let intervals: &[Interval<i32>] = interval_set.as_ref();
+    /// assert_eq!(intervals, &[Interval::new(1, 2), Interval::new(5, 6)]);
+    /// ```
+    fn as_ref(&self) -> &[Interval<Bound>] {
+        &self.intervals
+    }
+}
+
+impl<Bound: Width> Borrow<[Interval<Bound>]> for IntervalSet<Bound> {
+    fn borrow(&self) -> &[Interval<Bound>] {
+        &self.intervals
+    }
+}
+
 impl<Bound: Width> IntervalSet<Bound> {
     pub fn iter(&self) -> ::std::slice::Iter<Interval<Bound>> {
         self.intervals.iter()
     }
 
+    /// Iterates mutably over the intervals of the set.
+    /// Mutating the bounds through this iterator does not update the cached size
+    /// nor restore the ordering of the intervals, which breaks the invariants of the set.
+    /// Prefer [`IntervalSet::set_bounds`] to modify the intervals.
     pub fn iter_mut(&mut self) -> ::std::slice::IterMut<Interval<Bound>> {
         self.intervals.iter_mut()
     }
+
+    /// Iterates over the intervals of the set, from the highest to the lowest.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// let mut iter = interval_set.iter_intervals_rev();
+    /// assert_eq!(iter.next(), Some(&Interval::new(5, 6)));
+    /// assert_eq!(iter.next(), Some(&Interval::new(1, 2)));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_intervals_rev(&self) -> impl Iterator<Item = &Interval<Bound>> {
+        self.intervals.iter().rev()
+    }
+
+    /// Consumes the set and iterates over its intervals, from the highest to the lowest.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// let intervals: Vec<_> = interval_set.into_iter_rev().collect();
+    /// assert_eq!(intervals, vec![Interval::new(5, 6), Interval::new(1, 2)]);
+    /// ```
+    pub fn into_iter_rev(self) -> impl Iterator<Item = Interval<Bound>> {
+        self.intervals.into_iter().rev()
+    }
+
+    /// Iterates over the intervals of the set as `(lower, upper)` tuples, the format accepted by [`ToIntervalSet`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// assert_eq!(interval_set.tuple_iter().collect::<Vec<_>>(), vec![(1, 2), (5, 6)]);
+    /// ```
+    pub fn tuple_iter(&self) -> impl Iterator<Item = (Bound, Bound)> + '_ {
+        self.intervals
+            .iter()
+            .map(|i| (i.lower_ref().clone(), i.upper_ref().clone()))
+    }
+
+    /// Consumes the set and iterates over its intervals as `(lower, upper)` tuples.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// assert_eq!(interval_set.into_tuple_iter().collect::<Vec<_>>(), vec![(1, 2), (5, 6)]);
+    /// ```
+    pub fn into_tuple_iter(self) -> impl Iterator<Item = (Bound, Bound)> {
+        self.intervals
+            .into_iter()
+            .map(|i| (i.lower_ref().clone(), i.upper_ref().clone()))
+    }
+
+    /// Iterates over the bounds of the intervals of the set, alternating lower and upper bounds in increasing order.
+    /// A singleton interval yields its value twice.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (7, 9)].to_interval_set();
+    /// assert_eq!(interval_set.boundary_points().collect::<Vec<_>>(), vec![1, 3, 7, 9]);
+    /// ```
+    pub fn boundary_points(&self) -> impl Iterator<Item = Bound> + '_ {
+        self.tuple_iter()
+            .flat_map(|(lower, upper)| IntoIterator::into_iter([lower, upper]))
+    }
+
+    /// Consumes the set and returns its intervals, sorted and non-overlapping, without cloning them.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(5, 6), (1, 2)].to_interval_set();
+    /// assert_eq!(interval_set.into_interval_vec(), vec![Interval::new(1, 2), Interval::new(5, 6)]);
+    /// ```
+    pub fn into_interval_vec(self) -> Vec<Interval<Bound>> {
+        self.intervals
+    }
+
+    /// Folds the intervals of the set, from the lowest to the highest, stopping at the first error returned by `f`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6), (8, 20)].to_interval_set();
+    /// let total: Result<u32, ()> = interval_set.try_fold_intervals(0, |acc, i| Ok(acc + i.size()));
+    /// assert_eq!(total, Ok(17));
+    /// // Stops at the first interval above 4.
+    /// let found = interval_set.try_fold_intervals((), |(), i| if i.lower() > 4 { Err(i.clone()) } else { Ok(()) });
+    /// assert_eq!(found, Err(Interval::new(5, 6)));
+    /// ```
+    pub fn try_fold_intervals<B, E, F>(&self, init: B, f: F) -> Result<B, E>
+    where
+        F: FnMut(B, &Interval<Bound>) -> Result<B, E>,
+    {
+        self.intervals.iter().try_fold(init, f)
+    }
 }
 
 impl<Bound> IntervalSet<Bound>
@@ -185,1273 +388,4223 @@ where
         self.intervals.len()
     }
 
-    fn from_interval(i: Interval<Bound>) -> IntervalSet<Bound> {
-        let size = i.size().clone();
-        IntervalSet {
-            intervals: [i].to_vec(),
-            size,
-        }
+    /// Returns the index at which an interval starting at `value` would be inserted,
+    /// that is the number of intervals whose lower bound is strictly less than `value`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// assert_eq!(interval_set.partition_index(&0), 0);
+    /// assert_eq!(interval_set.partition_index(&2), 1);
+    /// assert_eq!(interval_set.partition_index(&5), 1);
+    /// assert_eq!(interval_set.partition_index(&9), 2);
+    /// ```
+    pub fn partition_index(&self, value: &Bound) -> usize {
+        self.intervals.partition_point(|i| &i.lower() < value)
     }
 
-    fn front(&self) -> &Interval<Bound> {
-        debug_assert!(
-            !self.is_empty(),
-            "Cannot access the first interval of an empty set."
-        );
-        &self.intervals[0]
+    /// Counts the intervals of the set overlapping the window `[lb, ub]`, in logarithmic time.
+    /// An empty window (`lb > ub`) overlaps no interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (6, 6), (9, 12)].to_interval_set();
+    /// assert_eq!(interval_set.num_intervals_in_range(2, 10), 3);
+    /// assert_eq!(interval_set.num_intervals_in_range(4, 8), 1);
+    /// assert_eq!(interval_set.num_intervals_in_range(7, 8), 0);
+    /// ```
+    pub fn num_intervals_in_range(&self, lb: Bound, ub: Bound) -> usize {
+        let first = self.intervals.partition_point(|i| i.upper() < lb);
+        let last = self.intervals.partition_point(|i| i.lower() <= ub);
+        last.saturating_sub(first)
     }
 
-    fn back_idx(&self) -> usize {
-        self.intervals.len() - 1
+    /// Checks whether every value of the window `[lb, ub]` is in the interval set, in logarithmic time.
+    /// As the intervals of the set are never joinable, the window must lie within a single interval.
+    /// An empty window (`lb > ub`) is always covered.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 5), (8, 10)].to_interval_set();
+    /// assert!(interval_set.covers_range(2, 4));
+    /// assert!(interval_set.covers_range(8, 10));
+    /// assert!(!interval_set.covers_range(4, 9));
+    /// assert!(interval_set.covers_range(7, 6));
+    /// ```
+    pub fn covers_range(&self, lb: Bound, ub: Bound) -> bool {
+        if lb > ub {
+            return true;
+        }
+        let idx = self.intervals.partition_point(|i| i.upper() < lb);
+        self.intervals
+            .get(idx)
+            .is_some_and(|i| i.lower() <= lb && ub <= i.upper())
     }
 
-    fn back(&self) -> &Interval<Bound> {
-        debug_assert!(
-            !self.is_empty(),
-            "Cannot access the last interval of an empty set."
-        );
-        &self.intervals[self.back_idx()]
+    /// Returns the smallest value of the interval set, or `None` if it is empty.
+    /// Unlike [`Bounded::lower`], this does not panic on an empty interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-5, 11), (20, 30)].to_interval_set().first_value(), Some(-5));
+    /// assert_eq!(IntervalSet::singleton(7).first_value(), Some(7));
+    /// assert_eq!(IntervalSet::<u8>::empty().first_value(), None);
+    /// ```
+    pub fn first_value(&self) -> Option<Bound> {
+        self.intervals.first().map(|i| i.lower())
     }
 
-    fn span(&self) -> Interval<Bound> {
-        if self.is_empty() {
-            Interval::empty()
-        } else {
-            self.span_slice(0, self.back_idx())
-        }
+    /// Returns the largest value of the interval set, or `None` if it is empty.
+    /// Unlike [`Bounded::upper`], this does not panic on an empty interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-5, 11), (20, 30)].to_interval_set().last_value(), Some(30));
+    /// assert_eq!(IntervalSet::singleton(7).last_value(), Some(7));
+    /// assert_eq!(IntervalSet::<u8>::empty().last_value(), None);
+    /// ```
+    pub fn last_value(&self) -> Option<Bound> {
+        self.intervals.last().map(|i| i.upper())
     }
 
-    fn span_slice(&self, left: usize, right: usize) -> Interval<Bound> {
-        Interval::new(self.intervals[left].lower(), self.intervals[right].upper())
+    /// Borrows the smallest value of the interval set, or returns `None` if it is empty.
+    /// This is the same as [`IntervalSet::first_value`] without cloning the bound.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-5, 11), (20, 30)].to_interval_set().lower_ref(), Some(&-5));
+    /// assert_eq!(IntervalSet::<u8>::empty().lower_ref(), None);
+    /// ```
+    pub fn lower_ref(&self) -> Option<&Bound> {
+        self.intervals.first().map(|i| i.lower_ref())
     }
 
-    fn push(&mut self, x: Interval<Bound>) {
-        debug_assert!(!x.is_empty(), "Cannot push empty interval.");
-        debug_assert!(self.is_empty() || !joinable(self.back(), &x),
-      "The intervals array must be ordered and intervals must not be joinable. For a safe push, use the union operation.");
-
-        self.size = self.size.clone() + x.size();
-        self.intervals.push(x);
+    /// Borrows the largest value of the interval set, or returns `None` if it is empty.
+    /// This is the same as [`IntervalSet::last_value`] without cloning the bound.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-5, 11), (20, 30)].to_interval_set().upper_ref(), Some(&30));
+    /// assert_eq!(IntervalSet::<u8>::empty().upper_ref(), None);
+    /// ```
+    pub fn upper_ref(&self) -> Option<&Bound> {
+        self.intervals.last().map(|i| i.upper_ref())
     }
 
-    fn pop(&mut self) -> Option<Interval<Bound>> {
-        if let Some(x) = self.intervals.pop() {
-            self.size = self.size.clone() - x.size();
-            Some(x)
-        } else {
-            None
+    /// Returns the smallest and largest values of the interval set, or `None` if it is empty.
+    /// Unlike [`IntervalSet::lower`] and [`IntervalSet::upper`], this never panics.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-5, 11), (20, 30)].to_interval_set().bounds(), Some((-5, 30)));
+    /// assert_eq!(IntervalSet::singleton(7).bounds(), Some((7, 7)));
+    /// assert_eq!(IntervalSet::<u8>::empty().bounds(), None);
+    /// ```
+    pub fn bounds(&self) -> Option<(Bound, Bound)> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some((first.lower(), last.upper())),
+            _ => None,
         }
     }
 
-    fn join_or_push(&mut self, x: Interval<Bound>) {
-        if self.is_empty() {
-            self.push(x);
-        } else {
-            debug_assert!(!x.is_empty(), "Cannot push empty interval.");
-            debug_assert!(self.back().lower() <= x.lower(),
-        "This operation is only for pushing interval to the back of the array, possibly overlapping with the last element.");
-
-            let joint = if joinable(self.back(), &x) {
-                self.pop().unwrap().hull(&x)
-            } else {
-                x
-            };
-            self.push(joint);
+    /// Constructs an interval set from intervals already satisfying the invariants of the set, without sorting or merging them.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let intervals = vec![Interval::new(1, 3), Interval::new(5, 9)];
+    /// let interval_set = unsafe { IntervalSet::from_sorted_unchecked(intervals) };
+    /// assert_eq!(interval_set, [(1, 3), (5, 9)].to_interval_set());
+    /// assert_eq!(interval_set.size(), 8 as u32);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The intervals must be non-empty, sorted by strictly increasing bounds and pairwise non-joinable:
+    /// there must be at least one value between two consecutive intervals (e.g. `[1..3]` and `[4..9]` must be given as `[1..9]`).
+    /// Otherwise, the other operations on the interval set have unspecified results.
+    /// This precondition is checked when debug assertions are enabled.
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// let intervals = vec![Interval::new(1, 3), Interval::new(4, 9)];
+    /// let _ = unsafe { IntervalSet::from_sorted_unchecked(intervals) }; // panics!
+    /// ```
+    pub unsafe fn from_sorted_unchecked(intervals: Vec<Interval<Bound>>) -> IntervalSet<Bound> {
+        #[cfg(debug_assertions)]
+        for (idx, interval) in intervals.iter().enumerate() {
+            assert!(
+                !interval.is_empty(),
+                "`from_sorted_unchecked` expects non-empty intervals."
+            );
+            assert!(
+                idx == 0 || !joinable(&intervals[idx - 1], interval),
+                "`from_sorted_unchecked` expects ordered and non-joinable intervals."
+            );
         }
+        let size = intervals
+            .iter()
+            .fold(<Bound as Width>::Output::zero(), |size, i| size + i.size());
+        IntervalSet { intervals, size }
     }
 
-    /// Expects the given iterable to be in-order - as we are iterating through
-    /// it, each [`Interval<Bound>`] should belong on the upper end of the
-    /// [`IntervalSet`]. Otherwise this method will panic.
-    fn extend_at_back<I>(&mut self, iterable: I)
+    /// Constructs an interval set from intervals sorted by lower bound, merging them in a single pass without sorting.
+    /// Unlike [`IntervalSet::from_sorted_unchecked`], the intervals may overlap, be adjacent or be empty.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let intervals = vec![Interval::new(1, 3), Interval::new(2, 5), Interval::new(6, 6), Interval::new(9, 9)];
+    /// let interval_set = IntervalSet::from_sorted_iter(intervals);
+    /// assert_eq!(interval_set, [(1, 6), (9, 9)].to_interval_set());
+    /// ```
+    /// The intervals must be sorted by lower bound, this precondition is checked when debug assertions are enabled.
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// let intervals = vec![Interval::new(5, 6), Interval::new(1, 2)];
+    /// let _ = IntervalSet::from_sorted_iter(intervals); // panics!
+    /// ```
+    pub fn from_sorted_iter<I>(iter: I) -> IntervalSet<Bound>
     where
         I: IntoIterator<Item = Interval<Bound>>,
-        Bound: PartialOrd,
     {
-        for interval in iterable {
-            self.join_or_push(interval);
+        let mut res = IntervalSet::empty();
+        for interval in iter.into_iter().filter(|i| !i.is_empty()) {
+            debug_assert!(
+                res.is_empty() || res.back().lower() <= interval.lower(),
+                "`from_sorted_iter` expects intervals sorted by lower bound."
+            );
+            res.join_or_push(interval);
         }
+        res
     }
 
-    fn find_interval_between(
-        &self,
-        value: &Bound,
-        mut left: usize,
-        mut right: usize,
-    ) -> (usize, usize) {
-        debug_assert!(left <= right);
-        debug_assert!(right < self.intervals.len());
-        debug_assert!(self.span_slice(left, right).contains(value));
+    /// Constructs the interval set containing the values of an interval.
+    /// Unlike `IntervalSet::new(i.lower(), i.upper())`, this accepts the empty interval, giving the empty set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::from_single(Interval::new(3, 7)), [(3, 7)].to_interval_set());
+    /// assert!(IntervalSet::from_single(Interval::<i32>::empty()).is_empty());
+    /// ```
+    pub fn from_single(i: Interval<Bound>) -> IntervalSet<Bound> {
+        IntervalSet::from_interval(i)
+    }
 
-        while left <= right {
-            let mid_idx = left + (right - left) / 2;
-            let mid = &self.intervals[mid_idx];
-            if &mid.lower() > value {
-                right = mid_idx - 1;
-            } else if &mid.upper() < value {
-                left = mid_idx + 1;
-            } else {
-                return (mid_idx, mid_idx);
+    /// Constructs the interval set containing the given values, in any order and possibly duplicated.
+    /// Each value is treated as a singleton, and consecutive values are merged in a single interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::from_values([1, 2, 3, 9]), [(1, 3), (9, 9)].to_interval_set());
+    /// assert_eq!(IntervalSet::from_values([9, 2, 1, 3]), [(1, 3), (9, 9)].to_interval_set());
+    /// assert_eq!(IntervalSet::from_values(vec![4, 4, 5, 4]), [(4, 5)].to_interval_set());
+    /// assert!(IntervalSet::<u8>::from_values(None).is_empty());
+    /// ```
+    pub fn from_values<I: IntoIterator<Item = Bound>>(values: I) -> IntervalSet<Bound> {
+        let mut values: Vec<Bound> = values.into_iter().collect();
+        values.sort_unstable();
+        IntervalSet::from_sorted_values(values)
+    }
+
+    /// Constructs the interval set containing the given inclusive ranges, in any order.
+    /// Overlapping or adjacent ranges are merged, and empty ranges are ignored.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::from_ranges(vec![5..=6, 0..=4]), IntervalSet::new(0, 6));
+    /// let ranges = vec![7..=9, 0..=3, 2..=5];
+    /// assert_eq!(IntervalSet::from_ranges(ranges.iter().cloned()), [(0, 5), (7, 9)].to_interval_set());
+    /// assert!(IntervalSet::<u8>::from_ranges(None).is_empty());
+    /// ```
+    pub fn from_ranges<I: IntoIterator<Item = RangeInclusive<Bound>>>(
+        ranges: I,
+    ) -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| intervals.extend(ranges.into_iter().map(|r| r.to_interval())));
+        res
+    }
+
+    // Precondition: `values` must be sorted, but can contain duplicates.
+    fn from_sorted_values<I: IntoIterator<Item = Bound>>(values: I) -> IntervalSet<Bound> {
+        let mut runs: Vec<Interval<Bound>> = Vec::new();
+        let mut values = values.into_iter();
+        if let Some(first) = values.next() {
+            let (mut lower, mut upper) = (first.clone(), first);
+            for value in values {
+                if value == upper {
+                    continue;
+                }
+                if value == upper.clone() + Bound::one() {
+                    upper = value;
+                } else {
+                    runs.push(Interval::new(lower, upper));
+                    lower = value.clone();
+                    upper = value;
+                }
             }
+            runs.push(Interval::new(lower, upper));
         }
-        (right, left)
+        let mut res = IntervalSet::empty();
+        res.extend_at_back(runs);
+        res
     }
 
-    // Returns the indexes of the left and right interval of `value`.
-    // If the value is outside `self`, returns None.
-    // If the value is inside one of the interval, the indexes will be equal.
-    fn find_interval(&self, value: &Bound) -> Option<(usize, usize)> {
-        if !self.span().contains(value) {
-            None
-        } else {
-            Some(self.find_interval_between(value, 0, self.back_idx()))
+    /// Checks the invariants of the interval set and describes the first one violated.
+    /// The intervals must be non-empty, sorted by their lower bound, non-joinable (otherwise they should have been merged),
+    /// and the cached size must be the sum of their sizes.
+    /// Interval sets built with the safe API always satisfy these invariants.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(1, 3), (5, 9)].to_interval_set().validate(), Ok(()));
+    /// assert_eq!(IntervalSet::<u8>::empty().validate(), Ok(()));
+    ///
+    /// let mut interval_set = [(1, 3), (5, 9)].to_interval_set();
+    /// for interval in interval_set.iter_mut() {
+    ///     *interval = Interval::new(2, 4);
+    /// }
+    /// assert!(interval_set.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        if self.intervals_size()? != self.size {
+            return Err(String::from(
+                "The cached size differs from the sum of the sizes of the intervals.",
+            ));
         }
+        Ok(())
     }
 
-    fn for_all_pairs<F>(&self, other: &IntervalSet<Bound>, f: F) -> IntervalSet<Bound>
-    where
-        F: Fn(&Interval<Bound>, &Interval<Bound>) -> Interval<Bound>,
-    {
-        let mut res = IntervalSet::empty();
-        for i in &self.intervals {
-            for j in &other.intervals {
-                res = res.union(&IntervalSet::from_interval(f(i, j)));
+    // Checks the invariants of the intervals and returns the sum of their sizes.
+    // The sizes are only added once the intervals are known to be disjoint, so the sum cannot overflow.
+    fn intervals_size(&self) -> Result<<Bound as Width>::Output, String> {
+        let mut size = <Bound as Width>::Output::zero();
+        for (idx, interval) in self.intervals.iter().enumerate() {
+            if interval.is_empty() {
+                return Err(format!("Interval #{} is empty.", idx));
+            }
+            if idx > 0 {
+                let previous = &self.intervals[idx - 1];
+                if previous.lower() >= interval.lower() {
+                    return Err(format!(
+                        "Intervals #{} and #{} are not sorted by lower bound.",
+                        idx - 1,
+                        idx
+                    ));
+                }
+                if joinable(previous, interval) {
+                    return Err(format!(
+                        "Intervals #{} and #{} are joinable and should have been merged.",
+                        idx - 1,
+                        idx
+                    ));
+                }
             }
+            size = size + interval.size();
         }
-        res
+        Ok(size)
     }
 
-    // Precondition: `f` must not change the size of the interval.
-    fn stable_map<F>(&self, f: F) -> IntervalSet<Bound>
-    where
-        F: Fn(&Interval<Bound>) -> Interval<Bound>,
-    {
-        IntervalSet {
-            intervals: self.intervals.iter().map(f).collect(),
-            size: self.size.clone(),
-        }
+    /// Checks that the intervals of the set are non-empty, sorted by their lower bound and non-joinable.
+    /// This is the same as [`IntervalSet::validate`] without checking the cached size,
+    /// and guarantees that iterating or displaying the set lists its intervals in ascending order.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!([(1, 3), (5, 9)].to_interval_set().is_canonical());
+    ///
+    /// let mut interval_set = [(1, 3), (5, 9)].to_interval_set();
+    /// for interval in interval_set.iter_mut() {
+    ///     *interval = Interval::new(2, 4);
+    /// }
+    /// assert!(!interval_set.is_canonical());
+    /// ```
+    pub fn is_canonical(&self) -> bool {
+        self.intervals.iter().all(|i| !i.is_empty())
+            && self
+                .intervals
+                .windows(2)
+                .all(|pair| pair[0].lower() < pair[1].lower() && !joinable(&pair[0], &pair[1]))
     }
 
-    fn map<F>(&self, f: F) -> IntervalSet<Bound>
-    where
-        F: Fn(&Interval<Bound>) -> Interval<Bound>,
-    {
-        self.intervals
-            .iter()
-            .fold(IntervalSet::empty(), |mut r, i| {
-                r.push(f(i));
-                r
-            })
+    /// Repairs an interval set containing empty intervals, which can only be introduced by breaking its invariants,
+    /// for instance through [`IntervalSet::iter_mut`].
+    /// The empty intervals are removed, the others are sorted and merged, and the cached size is recomputed.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut interval_set = [(1, 3), (5, 9), (12, 15)].to_interval_set();
+    /// for interval in interval_set.iter_mut().skip(1).take(1) {
+    ///     *interval = Interval::empty();
+    /// }
+    /// assert!(interval_set.validate().is_err());
+    /// interval_set.trim_empty_intervals();
+    /// assert_eq!(interval_set.validate(), Ok(()));
+    /// assert_eq!(interval_set, [(1, 3), (12, 15)].to_interval_set());
+    /// ```
+    pub fn trim_empty_intervals(&mut self) {
+        self.set_bounds(|_| {});
     }
-}
 
-fn joinable<Bound>(first: &Interval<Bound>, second: &Interval<Bound>) -> bool
-where
-    Bound: Width + Num,
-{
-    if first.upper() == Bound::max_value() {
-        true
-    } else {
-        first.upper() + Bound::one() >= second.lower()
+    /// Compares the intervals of two sets, ignoring their cached sizes.
+    /// Unlike `==`, this still recognises equal intervals when a size has been corrupted, which helps to diagnose broken invariants.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 5)].to_interval_set();
+    /// let mut corrupted = [(1, 3)].to_interval_set();
+    /// for interval in corrupted.iter_mut() {
+    ///     *interval = Interval::new(1, 5);
+    /// }
+    /// assert!(interval_set.structurally_eq(&corrupted));
+    /// assert_ne!(interval_set, corrupted);
+    /// ```
+    pub fn structurally_eq(&self, other: &IntervalSet<Bound>) -> bool {
+        self.intervals == other.intervals
     }
-}
 
-impl<Bound> Extend<Interval<Bound>> for IntervalSet<Bound>
-where
-    Bound: Width + Num,
-{
-    /// Inserts additional intervals into an interval set.
+    /// Modifies the intervals of the set with `rebuild` and restores the invariants afterwards.
+    /// The intervals are sorted, empty intervals are removed, overlapping or adjacent intervals are merged and the size is recomputed.
     /// ```
     /// # use interval::prelude::*;
-    /// let mut interval_set = IntervalSet::<u32>::empty();
-    /// assert_eq!(interval_set, Vec::new().to_interval_set());
-    /// interval_set.extend([Interval::new(2, 3), Interval::new(6, 7)]);
-    /// // Now the set contains two disjoint intervals.
-    /// assert_eq!(interval_set, [(2, 3), (6, 7)].to_interval_set());
-    /// // Unify the intervals with the missing items to create a single interval.
-    /// interval_set.extend([Interval::singleton(4), Interval::singleton(5)]);
-    /// assert_eq!(interval_set, [(2, 7)].to_interval_set());
+    /// let mut interval_set = [(1, 2), (6, 8)].to_interval_set();
+    /// interval_set.set_bounds(|intervals| {
+    ///     intervals[1] = Interval::new(3, 4);
+    ///     intervals.push(Interval::new(10, 10));
+    /// });
+    /// assert_eq!(interval_set, [(1, 4), (10, 10)].to_interval_set());
+    /// assert_eq!(interval_set.size(), 5 as u32);
     /// ```
-    fn extend<I>(&mut self, iterable: I)
+    pub fn set_bounds<F>(&mut self, rebuild: F)
     where
-        I: IntoIterator<Item = Interval<Bound>>,
+        F: FnOnce(&mut Vec<Interval<Bound>>),
     {
-        let mut intervals: Vec<_> = iterable.into_iter().map(|i| i.to_interval()).collect();
+        let mut intervals = std::mem::take(&mut self.intervals);
+        rebuild(&mut intervals);
+        intervals.retain(|i| !i.is_empty());
         intervals.sort_unstable_by_key(|i| i.lower());
-        let mut set = IntervalSet::empty();
-        set.extend_at_back(intervals);
-        *self = self.union(&set);
+        *self = IntervalSet::empty();
+        self.extend_at_back(intervals);
     }
-}
 
-impl<Bound: Width + Num> Eq for IntervalSet<Bound> {}
+    /// Constructs an interval set from intervals in any order, fusing two consecutive intervals when `should_join` holds.
+    /// `should_join` is called with the interval built so far and the next interval by increasing lower bound.
+    /// Overlapping or adjacent intervals are always merged, as the set cannot represent them separately.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let intervals = vec![Interval::new(8, 9), Interval::new(1, 2), Interval::new(5, 5)];
+    /// let within_3 = |a: &Interval<i32>, b: &Interval<i32>| b.lower() - a.upper() <= 3;
+    /// assert_eq!(IntervalSet::merge_with(intervals.clone(), within_3), [(1, 9)].to_interval_set());
+    /// assert_eq!(IntervalSet::merge_with(intervals, |_, _| false), [(1, 2), (5, 5), (8, 9)].to_interval_set());
+    /// ```
+    pub fn merge_with<F>(mut intervals: Vec<Interval<Bound>>, should_join: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Interval<Bound>, &Interval<Bound>) -> bool,
+    {
+        intervals.retain(|i| !i.is_empty());
+        intervals.sort_unstable_by_key(|i| i.lower());
+        let mut res = IntervalSet::empty();
+        for interval in intervals {
+            if !res.is_empty() && should_join(res.back(), &interval) {
+                let last = res.pop().unwrap();
+                res.join_or_push(last.hull(&interval));
+            } else {
+                res.join_or_push(interval);
+            }
+        }
+        res
+    }
 
-impl<Bound> PartialEq<IntervalSet<Bound>> for IntervalSet<Bound>
-where
-    Bound: Width + Num,
-{
-    // Checks whether two interval sets are the same.
+    /// Constructs the union of many interval sets at once: their intervals are gathered in a single buffer,
+    /// sorted once and merged in one pass, instead of building an intermediate set for every union.
     /// ```
     /// # use interval::prelude::*;
-    /// let single_interval = [(1, 5)].to_interval_set();
-    /// let equivalent_interval = [(1, 2), (2, 5)].to_interval_set();
-    /// assert_eq!(single_interval, equivalent_interval);
+    /// let sets = vec![[(8, 9)].to_interval_set(), [(0, 2), (5, 5)].to_interval_set(), [(3, 4)].to_interval_set()];
+    /// assert_eq!(IntervalSet::build_from_sets(sets), [(0, 5), (8, 9)].to_interval_set());
+    /// assert!(IntervalSet::<i32>::build_from_sets(vec![]).is_empty());
     /// ```
-    /// Empty intervals are the same as each other, but not non-empty intervals.
+    pub fn build_from_sets<I>(sets: I) -> IntervalSet<Bound>
+    where
+        I: IntoIterator<Item = IntervalSet<Bound>>,
+    {
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| {
+            for set in sets {
+                intervals.extend(set.intervals);
+            }
+        });
+        res
+    }
+
+    /// Replaces every interval of the set by the intervals returned by `f`, which may be in any order, overlap or be empty.
+    /// The resulting intervals are sorted and merged.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!(IntervalSet::<usize>::empty(), IntervalSet::<usize>::empty());
-    /// assert_ne!(IntervalSet::empty(), [(2, 3), (8, 9)].to_interval_set());
+    /// let interval_set = [(1, 2), (10, 11)].to_interval_set();
+    /// let ends = interval_set.flat_map_intervals(|i| vec![Interval::singleton(i.lower()), Interval::singleton(i.upper() + 5)]);
+    /// assert_eq!(ends, [(1, 1), (7, 7), (10, 10), (16, 16)].to_interval_set());
+    /// let none = interval_set.flat_map_intervals(|_| None);
+    /// assert!(none.is_empty());
     /// ```
-    fn eq(&self, other: &IntervalSet<Bound>) -> bool {
-        if self.size() != other.size() {
-            false
-        } else {
-            self.intervals == other.intervals
-        }
+    pub fn flat_map_intervals<F, I>(&self, f: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Interval<Bound>) -> I,
+        I: IntoIterator<Item = Interval<Bound>>,
+    {
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| intervals.extend(self.intervals.iter().flat_map(f)));
+        res
     }
-}
 
-impl<Bound> Range for IntervalSet<Bound>
-where
-    Bound: Width + Num,
-{
-    /// Constructs an interval set from a specified interval.
+    /// Splits the set into clusters, starting a new cluster after every gap of more than `threshold` missing values.
+    /// The clusters are in increasing order and their union is the set; the empty set has no cluster.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval = IntervalSet::new(2, 4);
-    /// assert!(interval.contains(&2));
-    /// assert!(interval.contains(&3));
-    /// assert!(interval.contains(&4));
-    ///
-    /// assert!(!interval.contains(&1));
-    /// assert!(!interval.contains(&5));
+    /// let interval_set = [(0, 1), (3, 4), (100, 101)].to_interval_set();
+    /// let clusters = interval_set.cluster_by_gap(5);
+    /// assert_eq!(clusters, vec![[(0, 1), (3, 4)].to_interval_set(), [(100, 101)].to_interval_set()]);
     /// ```
-    /// Constructing an empty interval set is done via the [IntervalSet::empty()] method.
+    pub fn cluster_by_gap(&self, threshold: Bound) -> Vec<IntervalSet<Bound>> {
+        let mut clusters: Vec<IntervalSet<Bound>> = Vec::new();
+        for interval in &self.intervals {
+            match clusters.last_mut() {
+                Some(cluster)
+                    if gap_at_most(cluster.back().upper(), interval.lower(), &threshold) =>
+                {
+                    cluster.push(interval.clone())
+                }
+                _ => clusters.push(IntervalSet::from_interval(interval.clone())),
+            }
+        }
+        clusters
+    }
+
+    /// Reduces the set to at most `max_intervals` intervals (and at least one if the set is not empty)
+    /// by filling the smallest gaps first, ties going to the leftmost gap.
+    /// The result is an over-approximation: it contains every value of the set.
     /// ```
     /// # use interval::prelude::*;
-    /// let empty_interval = IntervalSet::<u16>::empty();
+    /// let interval_set = [(0, 1), (3, 4), (10, 11), (13, 13), (30, 31)].to_interval_set();
+    /// assert_eq!(interval_set.clamp_count(2), [(0, 13), (30, 31)].to_interval_set());
+    /// assert_eq!(interval_set.clamp_count(5), interval_set);
     /// ```
-    fn new(lb: Bound, ub: Bound) -> IntervalSet<Bound> {
-        debug_assert!(lb <= ub, "Cannot build empty interval set with an invalid range. use crate::IntervalSet::empty().");
-        let i = Interval::new(lb, ub);
-        IntervalSet::from_interval(i)
+    pub fn clamp_count(&self, max_intervals: usize) -> IntervalSet<Bound> {
+        let to_fill = self.intervals.len().saturating_sub(max_intervals.max(1));
+        if to_fill == 0 {
+            return self.clone();
+        }
+        let mut gaps = self.gaps_by_width();
+        let mut filled = vec![false; self.intervals.len() - 1];
+        for _ in 0..to_fill {
+            let Reverse((_, idx)) = gaps.pop().unwrap();
+            filled[idx] = true;
+        }
+        self.fill_gaps(&filled)
     }
-}
 
-impl<Bound> Whole for IntervalSet<Bound>
-where
-    Bound: Width + Num,
-{
-    /// Constructs an interval set using the entire range an interval can represent.
-    /// The bounds of the interval set are bounded in the same way as [`Interval::whole`].
-    /// This is different for unsigned and signed integers:
+    /// Fills the smallest gaps of the set first, ties going to the leftmost gap, as long as the total number of values added
+    /// stays within `max_error`.
+    /// This greedy strategy fills as many gaps as possible for the budget, but not necessarily the most values.
+    /// The result is an over-approximation: it contains every value of the set, and at most `max_error` other values.
     /// ```
     /// # use interval::prelude::*;
-    /// let unsigned_interval = IntervalSet::<u8>::whole();
-    /// assert_eq!(unsigned_interval, IntervalSet::new(0, 254));
-    ///
-    /// let signed_interval = IntervalSet::<i8>::whole();
-    /// assert_eq!(signed_interval, IntervalSet::new(-127, 127));
+    /// let interval_set = [(0, 1), (3, 4), (6, 6), (11, 12)].to_interval_set();
+    /// assert_eq!(interval_set.simplify(3 as u32), [(0, 6), (11, 12)].to_interval_set());
+    /// assert_eq!(interval_set.simplify(6 as u32), [(0, 12)].to_interval_set());
+    /// assert_eq!(interval_set.simplify(0 as u32), interval_set);
     /// ```
-    fn whole() -> IntervalSet<Bound> {
+    pub fn simplify(&self, max_error: <Bound as Width>::Output) -> IntervalSet<Bound> {
+        if self.intervals.len() < 2 {
+            return self.clone();
+        }
+        let mut gaps = self.gaps_by_width();
+        let mut filled = vec![false; self.intervals.len() - 1];
+        let mut error = <Bound as Width>::Output::zero();
+        while let Some(Reverse((gap, idx))) = gaps.pop() {
+            if gap.clone() > max_error.clone() - error.clone() {
+                break;
+            }
+            error = error + gap;
+            filled[idx] = true;
+        }
+        self.fill_gaps(&filled)
+    }
+
+    // Returns the number of values between each pair of consecutive intervals, tagged with the index of the first one,
+    // in a heap popping the smallest gap first.
+    fn gaps_by_width(&self) -> BinaryHeap<Reverse<(<Bound as Width>::Output, usize)>> {
+        self.intervals
+            .windows(2)
+            .enumerate()
+            .map(|(idx, pair)| {
+                let gap = Bound::width(
+                    &(pair[0].upper() + Bound::one()),
+                    &(pair[1].lower() - Bound::one()),
+                );
+                Reverse((gap, idx))
+            })
+            .collect()
+    }
+
+    // Precondition: the set is not empty and `filled` has one entry per gap between consecutive intervals.
+    fn fill_gaps(&self, filled: &[bool]) -> IntervalSet<Bound> {
         let mut res = IntervalSet::empty();
-        res.push(Interval::whole());
+        let mut lower = self.front().lower();
+        for (idx, interval) in self.intervals.iter().enumerate() {
+            if idx == filled.len() || !filled[idx] {
+                res.push(Interval::new(lower.clone(), interval.upper()));
+                if idx < filled.len() {
+                    lower = self.intervals[idx + 1].lower();
+                }
+            }
+        }
         res
     }
-}
 
-impl<Bound> Bounded for IntervalSet<Bound>
-where
-    Bound: Width + Num + PartialOrd,
-{
-    /// Returns the smallest value contained in an interval set.
+    /// Calculates the smallest interval set made of a single interval containing both interval sets.
+    /// This is an over-approximation of the union that ignores the holes.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!([(8, 20)].to_interval_set().lower(), 8);
-    /// assert_eq!([(-5, 11), (10, 30)].to_interval_set().lower(), -5);
-    /// assert_eq!(IntervalSet::singleton(7).lower(), 7);
+    /// let a = [(1, 3), (8, 9)].to_interval_set();
+    /// let b = [(5, 6), (11, 12)].to_interval_set();
+    /// assert_eq!(a.hull_with(&b), IntervalSet::new(1, 12));
+    /// assert_eq!(a.hull_with(&IntervalSet::empty()), IntervalSet::new(1, 9));
+    /// assert!(IntervalSet::<i32>::empty().hull_with(&IntervalSet::empty()).is_empty());
+    /// ```
+    pub fn hull_with(&self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        let hull = self.span().hull(&other.span());
+        if hull.is_empty() {
+            IntervalSet::empty()
+        } else {
+            IntervalSet::from_interval(hull)
+        }
+    }
+
+    /// Interpolates between the hulls of two interval sets by the fraction `num / den` of the way from `self` to `other`.
+    /// The lower and upper bounds are interpolated independently and rounded down.
+    /// When one of the sets is empty, the hull of the other is returned.
     /// ```
-    /// However, this will panic on an empty interval.
-    /// ```should_panic
     /// # use interval::prelude::*;
-    /// IntervalSet::<u8>::empty().lower(); // panics!
+    /// let a = [(0, 2), (6, 10)].to_interval_set();
+    /// let b = [(20, 40)].to_interval_set();
+    /// assert_eq!(a.interpolate_hull(&b, 0, 2), IntervalSet::new(0, 10));
+    /// assert_eq!(a.interpolate_hull(&b, 1, 2), IntervalSet::new(10, 25));
+    /// assert_eq!(a.interpolate_hull(&b, 2, 2), IntervalSet::new(20, 40));
     /// ```
-    fn lower(&self) -> Bound {
-        debug_assert!(
-            !self.is_empty(),
-            "Cannot access lower bound on empty interval."
+    /// Panics unless `0 <= num <= den` and `den > 0`.
+    pub fn interpolate_hull(
+        &self,
+        other: &IntervalSet<Bound>,
+        num: Bound,
+        den: Bound,
+    ) -> IntervalSet<Bound>
+    where
+        Bound: Integer,
+    {
+        assert!(
+            den > Bound::zero() && num >= Bound::zero() && num <= den,
+            "The interpolation fraction must be in [0, 1] with a positive denominator."
         );
-        self.front().lower()
+        if self.is_empty() {
+            return other.hull_with(other);
+        }
+        if other.is_empty() {
+            return self.hull_with(self);
+        }
+        // Calculates `lo + floor((hi - lo) * n / den)` for `lo <= hi` and `n <= den` without overflowing:
+        // `hi - lo` is split at zero when it is not representable, and each part is multiplied by `n / den` separately.
+        let lerp_up = |lo: Bound, hi: Bound, n: Bound| {
+            let (above, below) = if lo < Bound::zero() && hi > Bound::zero() {
+                (hi, Bound::zero() - lo.clone())
+            } else {
+                (hi - lo.clone(), Bound::zero())
+            };
+            let scale = |x: Bound| {
+                let (q, r) = mul_div_rem(x.mod_floor(&den), n.clone(), &den);
+                (x.div_floor(&den) * n.clone() + q, r)
+            };
+            let (above_q, above_r) = scale(above);
+            let (below_q, below_r) = scale(below);
+            let carry = if above_r >= den.clone() - below_r {
+                Bound::one()
+            } else {
+                Bound::zero()
+            };
+            // `lo + below_q` is at most zero when `hi - lo` was split, so no partial sum overflows.
+            lo + below_q + above_q + carry
+        };
+        // Moving down by `ceil(d * num / den)` from `from` is moving up by `floor(d * (den - num) / den)` from `to`.
+        let lerp = |from: Bound, to: Bound| {
+            if from <= to {
+                lerp_up(from, to, num.clone())
+            } else {
+                lerp_up(to, from, den.clone() - num.clone())
+            }
+        };
+        IntervalSet::new(
+            lerp(self.lower(), other.lower()),
+            lerp(self.upper(), other.upper()),
+        )
     }
 
-    /// Returns the largest value contained in an interval set.
+    /// Splits the interval set into consecutive interval sets containing at most `max_size` values each.
+    /// Intervals larger than `max_size` are cut into several pieces.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!([(8, 20)].to_interval_set().upper(), 20);
-    /// assert_eq!([(-5, 11), (10, 30)].to_interval_set().upper(), 30);
-    /// assert_eq!(IntervalSet::singleton(7).upper(), 7);
+    /// let chunks = [(0, 9)].to_interval_set().split_into_chunks(4 as u32);
+    /// assert_eq!(chunks, vec![IntervalSet::new(0, 3), IntervalSet::new(4, 7), IntervalSet::new(8, 9)]);
+    ///
+    /// let chunks = [(0, 1), (5, 7), (9, 9)].to_interval_set().split_into_chunks(3 as u32);
+    /// assert_eq!(chunks, vec![[(0, 1), (5, 5)].to_interval_set(), [(6, 7), (9, 9)].to_interval_set()]);
     /// ```
-    /// However, this will panic on an empty interval.
+    /// The maximum size must be positive.
     /// ```should_panic
     /// # use interval::prelude::*;
-    /// IntervalSet::<u8>::empty().upper(); // panics!
+    /// let _ = IntervalSet::new(0, 9).split_into_chunks(0 as u32); // panics!
     /// ```
-    fn upper(&self) -> Bound {
-        debug_assert!(
-            !self.is_empty(),
-            "Cannot access upper bound on empty interval."
+    pub fn split_into_chunks(&self, max_size: <Bound as Width>::Output) -> Vec<IntervalSet<Bound>>
+    where
+        Bound: NumCast,
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        assert!(
+            max_size > <Bound as Width>::Output::zero(),
+            "Cannot split an interval set into chunks of size zero."
         );
-        self.back().upper()
+        let mut chunks = Vec::new();
+        let mut chunk = IntervalSet::empty();
+        for i in &self.intervals {
+            let mut lower = i.lower();
+            loop {
+                let remaining = max_size.clone() - chunk.size();
+                if Bound::width(&lower, &i.upper()) <= remaining {
+                    chunk.push(Interval::new(lower, i.upper()));
+                    break;
+                }
+                let upper = add_width(lower.clone(), remaining - <Bound as Width>::Output::one());
+                chunk.push(Interval::new(lower, upper.clone()));
+                chunks.push(std::mem::replace(&mut chunk, IntervalSet::empty()));
+                lower = upper + Bound::one();
+            }
+            if chunk.size() == max_size {
+                chunks.push(std::mem::replace(&mut chunk, IntervalSet::empty()));
+            }
+        }
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks
     }
-}
 
-impl<Bound: Width + Num> Singleton for IntervalSet<Bound> {
-    /// Constructs an interval set containing a single value.
-    /// This set contains a single interval with the same upper and lower bounds.
+    /// Keeps the `max` smallest values of the interval set.
+    /// This is the first chunk of [`IntervalSet::split_into_chunks`].
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set_5 = IntervalSet::singleton(5);
-    /// assert_eq!(interval_set_5.size(), 1 as u32);
-    /// assert_eq!(interval_set_5.lower(), interval_set_5.upper());
-    /// assert!(interval_set_5.contains(&5));
-    /// assert!(!interval_set_5.is_empty());
+    /// let interval_set = [(0, 4), (10, 14)].to_interval_set();
+    /// assert_eq!(interval_set.trim_to_size(7 as u32), [(0, 4), (10, 11)].to_interval_set());
+    /// assert_eq!(interval_set.trim_to_size(5 as u32), [(0, 4)].to_interval_set());
+    /// assert_eq!(interval_set.trim_to_size(20 as u32), interval_set);
+    /// assert!(interval_set.trim_to_size(0 as u32).is_empty());
     /// ```
-    fn singleton(x: Bound) -> IntervalSet<Bound> {
-        IntervalSet::new(x.clone(), x)
+    pub fn trim_to_size(&self, max: <Bound as Width>::Output) -> IntervalSet<Bound>
+    where
+        Bound: NumCast,
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        if self.size() <= max {
+            return self.clone();
+        }
+        let mut res = IntervalSet::empty();
+        for i in &self.intervals {
+            let remaining = max.clone() - res.size();
+            if remaining.is_zero() {
+                break;
+            } else if i.size() <= remaining {
+                res.push(i.clone());
+            } else {
+                let upper = add_width(i.lower(), remaining - <Bound as Width>::Output::one());
+                res.push(Interval::new(i.lower(), upper));
+                break;
+            }
+        }
+        res
     }
-}
 
-impl<Bound: Width + Num> Empty for IntervalSet<Bound> {
-    /// Constructs an empty interval set.
-    /// The type needs to be specified or inferred as `Interval` is parametrized by its input.
+    /// Multiplies every value of the interval set by the fraction `num / den`, rounding the bounds of each interval with `mode`.
+    /// Intervals colliding after scaling are merged.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!(IntervalSet::<i32>::empty().size(), 0);
-    /// assert_eq!(IntervalSet::<u32>::empty().size(), 0);
-    /// assert!(IntervalSet::<u16>::empty().is_empty());
+    /// let interval_set = [(1, 3), (10, 12)].to_interval_set();
+    /// assert_eq!(interval_set.scale_round(1, 2, RoundMode::Floor), [(0, 1), (5, 6)].to_interval_set());
+    /// assert_eq!(interval_set.scale_round(1, 2, RoundMode::Ceil), [(1, 2), (5, 6)].to_interval_set());
+    /// assert_eq!(interval_set.scale_round(1, 2, RoundMode::Nearest), [(1, 2), (5, 6)].to_interval_set());
+    /// assert_eq!(interval_set.scale_round(1, 2, RoundMode::Outward), [(0, 2), (5, 6)].to_interval_set());
+    /// assert_eq!(interval_set.scale_round(-3, 2, RoundMode::Outward), [(-18, -15), (-5, -1)].to_interval_set());
     /// ```
-    fn empty() -> IntervalSet<Bound> {
-        IntervalSet {
-            intervals: Vec::new(),
-            size: <<Bound as Width>::Output>::zero(),
-        }
+    /// The rounding mode [`RoundMode::Outward`] guarantees that the result is an over-approximation:
+    /// it contains the rounding up and down of every scaled value.
+    /// As with the other arithmetic operations, nothing is done against overflow of `value * num`.
+    pub fn scale_round(&self, num: Bound, den: Bound, mode: RoundMode) -> IntervalSet<Bound>
+    where
+        Bound: Integer,
+    {
+        assert!(
+            !den.is_zero(),
+            "Cannot scale an interval set by a fraction with a zero denominator."
+        );
+        let (num, den) = if den < Bound::zero() {
+            (Bound::zero() - num, Bound::zero() - den)
+        } else {
+            (num, den)
+        };
+        let (lower_mode, upper_mode) = match mode {
+            RoundMode::Outward => (RoundMode::Floor, RoundMode::Ceil),
+            mode => (mode, mode),
+        };
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| {
+            intervals.extend(self.intervals.iter().map(|i| {
+                let a = i.lower() * num.clone();
+                let b = i.upper() * num.clone();
+                let (a, b) = if a <= b { (a, b) } else { (b, a) };
+                Interval::new(
+                    div_round(a, &den, lower_mode),
+                    div_round(b, &den, upper_mode),
+                )
+            }))
+        });
+        res
     }
-}
-
-/// `IsSingleton` and `IsEmpty` are defined automatically in `gcollections`.
-impl<Bound: Width + Num> Cardinality for IntervalSet<Bound> {
-    type Size = <Bound as Width>::Output;
 
-    /// Calculates the size of an interval set (the number of integers it contains).
-    /// This includes the endpoints of all intervals.
-    /// The size of the interval set is unsigned, but has the same number of bits as the interval bounds.
+    /// Widens every interval to whole blocks of `m` values: lower bounds are rounded down to a multiple of `m`
+    /// and upper bounds are rounded up to one less than a multiple of `m`. Intervals fused by the alignment are merged.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!(IntervalSet::<i32>::singleton(8).size(), 1 as u32);
-    /// assert_eq!(IntervalSet::<usize>::new(1, 10).size(), 10 as usize);
-    /// // Default is to use i32.
-    /// assert_eq!([(3, 5), (8, 9)].to_interval_set().size(), (3 + 2) as u32);
-    /// assert_eq!(IntervalSet::<i64>::empty().size(), 0);
-    /// // Doesn't overflow:
-    /// assert_eq!(IntervalSet::<usize>::whole().size(), usize::max_value());
+    /// let interval_set = [(3, 5), (13, 13)].to_interval_set();
+    /// assert_eq!(interval_set.expand_to_multiple(4), [(0, 7), (12, 15)].to_interval_set());
+    /// assert_eq!(IntervalSet::new(-3, 2).expand_to_multiple(4), IntervalSet::new(-4, 3));
     /// ```
-    fn size(&self) -> <Bound as Width>::Output {
-        self.size.clone()
+    /// Panics if `m` is not positive.
+    pub fn expand_to_multiple(&self, m: Bound) -> IntervalSet<Bound>
+    where
+        Bound: Integer,
+    {
+        assert!(
+            m > Bound::zero(),
+            "Cannot align an interval set to multiples of a non-positive value."
+        );
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| {
+            intervals.extend(self.intervals.iter().map(|i| {
+                let lower = i.lower().div_floor(&m) * m.clone();
+                let upper = i.upper().div_floor(&m) * m.clone() + (m.clone() - Bound::one());
+                Interval::new(lower, upper)
+            }))
+        });
+        res
     }
-}
 
-impl<Bound: Width + Num> Contains for IntervalSet<Bound> {
-    /// Calculates whether an interval contains a value.
+    /// Shifts every value of the set by `shift` modulo `modulus`, so that the result lies in `[0, modulus - 1]`.
+    /// An interval wrapping past `modulus - 1` is split in two, and intervals fused by the rotation are merged.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
-    /// assert!(interval_set.contains(&3));
-    /// assert!(interval_set.contains(&4));
-    /// assert!(interval_set.contains(&5));
-    /// assert!(interval_set.contains(&8));
-    /// assert!(interval_set.contains(&9));
-    ///
-    /// assert!(!interval_set.contains(&1));
-    /// assert!(!interval_set.contains(&2));
-    /// assert!(!interval_set.contains(&6));
-    /// assert!(!interval_set.contains(&7));
-    /// assert!(!interval_set.contains(&10));
+    /// let interval_set = [(2, 4)].to_interval_set();
+    /// assert_eq!(interval_set.rotate(3, 6), [(0, 1), (5, 5)].to_interval_set());
+    /// assert_eq!(interval_set.rotate(-3, 6), [(0, 1), (5, 5)].to_interval_set());
+    /// assert_eq!([(-1, 0), (9, 9)].to_interval_set().rotate(0, 4), [(0, 1), (3, 3)].to_interval_set());
     /// ```
-    fn contains(&self, value: &Bound) -> bool {
-        if let Some((left, right)) = self.find_interval(value) {
-            left == right
-        } else {
-            false
-        }
+    /// Panics if `modulus` is not positive.
+    pub fn rotate(&self, shift: Bound, modulus: Bound) -> IntervalSet<Bound>
+    where
+        Bound: Integer,
+    {
+        assert!(
+            modulus > Bound::zero(),
+            "Cannot rotate an interval set within a non-positive modulus."
+        );
+        let last = modulus.clone() - Bound::one();
+        let shift = shift.mod_floor(&modulus);
+        // Rotates a value of `[0, modulus - 1]` without computing `value + shift`, which could overflow.
+        let rotate_value = |v: Bound| {
+            if v >= modulus.clone() - shift.clone() {
+                v - (modulus.clone() - shift.clone())
+            } else {
+                v + shift.clone()
+            }
+        };
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| {
+            let mut push_rotated = |lower: Bound, upper: Bound| {
+                let (lower, upper) = (rotate_value(lower), rotate_value(upper));
+                if lower <= upper {
+                    intervals.push(Interval::new(lower, upper));
+                } else {
+                    intervals.push(Interval::new(lower, last.clone()));
+                    intervals.push(Interval::new(Bound::zero(), upper));
+                }
+            };
+            for i in &self.intervals {
+                let (lower_turn, lower) = i.lower().div_mod_floor(&modulus);
+                let (upper_turn, upper) = i.upper().div_mod_floor(&modulus);
+                if lower_turn == upper_turn {
+                    push_rotated(lower, upper);
+                } else if upper_turn == lower_turn + Bound::one()
+                    && upper.clone() + Bound::one() < lower
+                {
+                    push_rotated(lower, last.clone());
+                    push_rotated(Bound::zero(), upper);
+                } else {
+                    push_rotated(Bound::zero(), last.clone());
+                }
+            }
+        });
+        res
     }
-}
-
-fn advance_one<I, F, Item>(a: &mut Peekable<I>, b: &mut Peekable<I>, choose: F) -> Item
-where
-    I: Iterator<Item = Item>,
-    F: Fn(&Item, &Item) -> bool,
-    Item: Bounded,
-{
-    static NON_EMPTY_PRECONDITION: &str =
-        "`advance_one` expects both interval iterators to be non_empty.";
-    let who_advance = {
-        let i = a.peek().expect(NON_EMPTY_PRECONDITION);
-        let j = b.peek().expect(NON_EMPTY_PRECONDITION);
-        choose(i, j)
-    };
-    let to_advance = if who_advance { a } else { b };
-    to_advance.next().unwrap()
-}
-
-fn advance_lower<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> Item
-where
-    I: Iterator<Item = Item>,
-    Item: Bounded + Collection<Item = B>,
-    B: Ord,
-{
-    advance_one(a, b, |i, j| i.lower() < j.lower())
-}
-
-// Advance the one with the lower upper bound.
-fn advance_lub<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> Item
-where
-    I: Iterator<Item = Item>,
-    Item: Bounded + Collection<Item = B>,
-    B: Ord,
-{
-    advance_one(a, b, |i, j| i.upper() < j.upper())
-}
 
-fn from_lower_iterator<I, Bound>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> IntervalSet<Bound>
-where
-    I: Iterator<Item = Interval<Bound>>,
-    Bound: Width + Num,
-{
-    if a.peek().is_none() || b.peek().is_none() {
-        IntervalSet::empty()
-    } else {
-        let first = advance_lower(a, b);
-        IntervalSet::from_interval(first)
+    /// Renumbers the values of the set to the contiguous interval `[0, size() - 1]` (or the empty set), preserving their order.
+    /// The mapping table contains, for each interval of the set, its lower bound and the position of this lower bound in the compacted set:
+    /// a value `v` of the interval starting at `lower` is renumbered to `position + (v - lower)`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(10, 12), (20, 21), (30, 30)].to_interval_set();
+    /// let (compact, mapping) = interval_set.compact();
+    /// assert_eq!(compact, IntervalSet::new(0, 5));
+    /// assert_eq!(mapping, vec![(10, 0), (20, 3), (30, 5)]);
+    /// ```
+    /// Panics if `size() - 1` is not representable by the bound.
+    pub fn compact(&self) -> (IntervalSet<Bound>, Vec<(Bound, Bound)>)
+    where
+        Bound: NumCast,
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        let mut position = <Bound as Width>::Output::zero();
+        let mut mapping = Vec::with_capacity(self.intervals.len());
+        for interval in &self.intervals {
+            mapping.push((interval.lower(), add_width(Bound::zero(), position.clone())));
+            position = position + interval.size();
+        }
+        let compact = if self.is_empty() {
+            IntervalSet::empty()
+        } else {
+            let last = add_width(Bound::zero(), self.size() - <Bound as Width>::Output::one());
+            IntervalSet::new(Bound::zero(), last)
+        };
+        (compact, mapping)
     }
-}
-
-impl<Bound> Union<Bound> for IntervalSet<Bound>
-where
-    Bound: Width + Num + Clone,
-{
-    type Output = IntervalSet<Bound>;
 
-    /// Adds a value to the interval set.
-    /// The value does not have to below to an existing interval,
-    /// but if it does, those intervals will be merged.
+    /// Calculates the sum of all the values contained in the set, with the arithmetic series formula on each interval.
+    /// The sum is accumulated in an `i128`, which cannot overflow for the primitive integer bounds up to 64 bits.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(1, 4), (6, 7)].to_interval_set();
-    /// assert_eq!(interval_set.union(&10), [(1, 4), (6, 7), (10, 10)].to_interval_set());
-    ///
-    /// assert_eq!(interval_set.union(&3), interval_set);
-    /// assert_eq!(interval_set.union(&6), interval_set);
-    ///
-    /// assert_eq!(interval_set.union(&0), [(0, 4), (6, 7)].to_interval_set());
-    /// assert_eq!(interval_set.union(&8), [(1, 4), (6, 8)].to_interval_set());
-    ///
-    /// assert_eq!(interval_set.union(&5), [(1, 7)].to_interval_set());
+    /// assert_eq!([(1, 3), (10, 10)].to_interval_set().sum_of_values(), 16);
+    /// assert_eq!([(-4, 2)].to_interval_set().sum_of_values(), -7);
+    /// assert_eq!(IntervalSet::<u8>::empty().sum_of_values(), 0);
     /// ```
-    fn union(&self, rhs: &Bound) -> IntervalSet<Bound> {
-        self.union(&IntervalSet::singleton(rhs.clone()))
+    /// Panics if a bound is not representable by an `i128`.
+    pub fn sum_of_values(&self) -> i128
+    where
+        Bound: ToPrimitive,
+    {
+        self.intervals
+            .iter()
+            .map(|i| {
+                let lower = i
+                    .lower()
+                    .to_i128()
+                    .expect("The bound must fit in an `i128`.");
+                let upper = i
+                    .upper()
+                    .to_i128()
+                    .expect("The bound must fit in an `i128`.");
+                let count = upper - lower + 1;
+                // One of `count` and `lower + upper` is even, halving it first keeps the product equal to the sum.
+                if count % 2 == 0 {
+                    count / 2 * (lower + upper)
+                } else {
+                    (lower + upper) / 2 * count
+                }
+            })
+            .sum()
     }
-}
-
-impl<Bound: Width + Num> Union for IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates the union of two interval sets.
-    /// This returns an interval set containing all the values in the two that were unified.
+    /// Returns the common difference of the set when it is made of at least two equally spaced singletons, and `None` otherwise.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
-    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
-    /// assert_eq!(a.union(&b), [(1, 5), (7, 8), (10, 15)].to_interval_set());
+    /// assert_eq!([(0, 0), (3, 3), (6, 6), (9, 9)].to_interval_set().detect_step(), Some(3));
+    /// assert_eq!([(0, 0), (3, 3), (5, 5)].to_interval_set().detect_step(), None);
+    /// assert_eq!([(0, 1), (4, 5)].to_interval_set().detect_step(), None);
+    /// assert_eq!(IntervalSet::singleton(4).detect_step(), None);
     /// ```
-    fn union(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        let a = &mut self.intervals.iter().cloned().peekable();
-        let b = &mut rhs.intervals.iter().cloned().peekable();
-        let mut res = from_lower_iterator(a, b);
-        while a.peek().is_some() && b.peek().is_some() {
-            let lower = advance_lower(a, b);
-            res.join_or_push(lower);
+    /// A step that is not representable by the bound is not detected.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(-2_000_000_000, -2_000_000_000), (2_000_000_000, 2_000_000_000)].to_interval_set().detect_step(), None);
+    /// ```
+    pub fn detect_step(&self) -> Option<Bound>
+    where
+        Bound: CheckedSub,
+    {
+        if self.intervals.len() < 2 || self.intervals.iter().any(|i| i.lower() != i.upper()) {
+            return None;
         }
-        res.extend_at_back(a);
-        res.extend_at_back(b);
-        res
-    }
-}
-
-// Returns `false` when one of the iterator is consumed.
-// Iterators are not consumed if the intervals are already overlapping.
-fn advance_to_first_overlapping<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> bool
-where
-    I: Iterator<Item = Item>,
-    Item: Bounded + Overlap + Collection<Item = B>,
-    B: Ord,
-{
-    while a.peek().is_some() && b.peek().is_some() {
-        let overlapping = {
-            let i = a.peek().unwrap();
-            let j = b.peek().unwrap();
-            i.overlap(j)
-        };
-        if overlapping {
-            return true;
+        let step = self.intervals[1]
+            .lower()
+            .checked_sub(&self.front().lower())?;
+        if self
+            .intervals
+            .windows(2)
+            .all(|pair| pair[1].lower().checked_sub(&pair[0].lower()) == Some(step.clone()))
+        {
+            Some(step)
         } else {
-            advance_lower(a, b);
+            None
         }
     }
-    false
-}
-
-impl<Bound> Intersection<Bound> for IntervalSet<Bound>
-where
-    Bound: Width + Num + Clone,
-{
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates whether a value is contained in an interval.
-    /// Returns the value as an interval set if it is in the interval
-    /// and an empty interval set if not.
+    /// Describes the set as the progression `(start, step, count)` when it is made of equally spaced singletons,
+    /// i.e. the values `start + k * step` for `k` in `[0, count - 1]`, and returns `None` otherwise.
+    /// By convention, a set containing a single value is the progression `(value, 0, 1)`.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(1, 4), (6, 7)].to_interval_set();
-    /// assert_eq!(interval_set.intersection(&10), IntervalSet::empty());
-    ///
-    /// assert_eq!(interval_set.intersection(&3), IntervalSet::singleton(3));
-    /// assert_eq!(interval_set.intersection(&6), IntervalSet::singleton(6));
+    /// let progression = [(0, 0), (3, 3), (6, 6), (9, 9)].to_interval_set();
+    /// assert_eq!(progression.as_arithmetic_progression(), Some((0, 3, 4 as u32)));
+    /// assert_eq!(IntervalSet::singleton(4).as_arithmetic_progression(), Some((4, 0, 1 as u32)));
+    /// assert_eq!([(0, 0), (3, 3), (6, 6), (8, 8)].to_interval_set().as_arithmetic_progression(), None);
+    /// assert_eq!(IntervalSet::<i32>::empty().as_arithmetic_progression(), None);
     /// ```
-    fn intersection(&self, rhs: &Bound) -> IntervalSet<Bound> {
-        self.intersection(&IntervalSet::singleton(rhs.clone()))
+    pub fn as_arithmetic_progression(&self) -> Option<(Bound, Bound, <Bound as Width>::Output)>
+    where
+        Bound: CheckedSub,
+    {
+        let step = if self.is_singleton() {
+            Bound::zero()
+        } else {
+            self.detect_step()?
+        };
+        // Every interval is a singleton, so the size of the set is the number of terms.
+        Some((self.front().lower(), step, self.size()))
     }
-}
-
-impl<Bound: Width + Num> Intersection for IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates the intersection of two interval sets.
-    /// This returns an interval set containing all the values in the both.
+    /// Counts the intervals of the set by order of magnitude of their size:
+    /// the key `k` maps to the number of intervals whose size is in `[2^k, 2^(k+1))`.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
-    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
-    /// assert_eq!(a.intersection(&b), [(2, 3), (8, 8)].to_interval_set());
+    /// # use std::collections::BTreeMap;
+    /// let interval_set = [(0, 0), (2, 4), (10, 17), (20, 28)].to_interval_set();
+    /// let histogram = interval_set.size_histogram();
+    /// assert_eq!(histogram, BTreeMap::from([(0, 1), (1, 1), (3, 2)]));
+    /// assert!(IntervalSet::<i32>::empty().size_histogram().is_empty());
     /// ```
-    fn intersection(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        let a = &mut self.intervals.iter().cloned().peekable();
-        let b = &mut rhs.intervals.iter().cloned().peekable();
-        let mut res = IntervalSet::empty();
-        while advance_to_first_overlapping(a, b) {
-            {
-                let i = a.peek().unwrap();
-                let j = b.peek().unwrap();
-                res.push(i.intersection(j));
-            }
-            advance_lub(a, b); // advance the one with the lowest upper bound.
+    pub fn size_histogram(&self) -> BTreeMap<u32, usize> {
+        let mut histogram = BTreeMap::new();
+        for interval in &self.intervals {
+            *histogram
+                .entry(bit_length(interval.size()) - 1)
+                .or_insert(0) += 1;
         }
-        res
+        histogram
     }
-}
 
-fn push_left_complement<Bound: Width + Num>(x: &Interval<Bound>, res: &mut IntervalSet<Bound>) {
-    let min = <Bound as Width>::min_value();
-    if x.lower() != min {
-        res.push(Interval::new(min, x.lower() - Bound::one()));
+    /// Counts the values of the smallest interval containing the set, or returns zero if it is empty.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(0, 0), (9, 9)].to_interval_set().span_size(), 10 as u32);
+    /// assert_eq!(IntervalSet::<i32>::empty().span_size(), 0 as u32);
+    /// ```
+    pub fn span_size(&self) -> <Bound as Width>::Output {
+        if self.is_empty() {
+            <Bound as Width>::Output::zero()
+        } else {
+            self.span().size()
+        }
     }
-}
 
-fn push_right_complement<Bound: Width + Num>(x: &Interval<Bound>, res: &mut IntervalSet<Bound>) {
-    let max = <Bound as Width>::max_value();
-    if x.upper() != max {
-        res.push(Interval::new(x.upper() + Bound::one(), max));
+    /// Counts the values missing from the set between its smallest and largest values, i.e. the total size of its gaps.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(0, 0), (9, 9)].to_interval_set().total_gap_size(), 8 as u32);
+    /// assert_eq!([(0, 9)].to_interval_set().total_gap_size(), 0 as u32);
+    /// ```
+    pub fn total_gap_size(&self) -> <Bound as Width>::Output {
+        self.span_size() - self.size()
     }
-}
 
-impl<Bound: Width + Num> Complement for IntervalSet<Bound> {
-    /// Calculates all values that are excluded from the interval set.
-    /// Positive and negative infinity are represented with `Interval::whole().lower()` and `Interval::whole().upper()`;
+    /// Finds the first gap between two intervals of the set that contains at least `size` missing values.
+    /// The values below and above the set are not considered, see [`IntervalSet::first_gap_of_size_within`].
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!(IntervalSet::<i64>::empty().complement(), IntervalSet::whole());
-    ///
-    /// let neg_inf = IntervalSet::<i32>::whole().lower();
-    /// let pos_inf = IntervalSet::<i32>::whole().upper();
-    /// assert_eq!(IntervalSet::singleton(5).complement(), [(neg_inf, 4), (6, pos_inf)].to_interval_set());
-    /// assert_eq!([(2, 5), (8, 10)].to_interval_set().complement(), [(neg_inf, 1), (6, 7), (11, pos_inf)].to_interval_set());
+    /// let interval_set = [(0, 1), (5, 5), (10, 20)].to_interval_set();
+    /// assert_eq!(interval_set.first_gap_of_size(2 as u32), Some(Interval::new(2, 4)));
+    /// assert_eq!(interval_set.first_gap_of_size(4 as u32), Some(Interval::new(6, 9)));
+    /// assert_eq!(interval_set.first_gap_of_size(5 as u32), None);
     /// ```
-    fn complement(&self) -> IntervalSet<Bound> {
-        let mut res = IntervalSet::empty();
-        if self.is_empty() {
-            res.push(Interval::whole());
-        } else {
-            let one = Bound::one();
-            push_left_complement(self.front(), &mut res);
-            for i in 1..self.intervals.len() {
-                let current = &self.intervals[i];
-                let previous = &self.intervals[i - 1];
-                res.push(Interval::new(
-                    previous.upper() + one.clone(),
-                    current.lower() - one.clone(),
-                ));
-            }
-            push_right_complement(self.back(), &mut res);
-        }
-        res
+    pub fn first_gap_of_size(&self, size: <Bound as Width>::Output) -> Option<Interval<Bound>> {
+        self.intervals
+            .windows(2)
+            .map(|pair| {
+                Interval::new(
+                    pair[0].upper() + Bound::one(),
+                    pair[1].lower() - Bound::one(),
+                )
+            })
+            .find(|gap| gap.size() >= size)
     }
-}
-
-impl<Bound> Difference<Bound> for IntervalSet<Bound>
-where
-    Bound: Width + Num + Clone,
-{
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates the interval set after removing a single value.
-    /// This returns the original interval set if the value is not in the interval set.
+    /// Finds the first run of at least `size` values of `[lb, ub]` missing from the set.
+    /// Unlike [`IntervalSet::first_gap_of_size`], the values of `[lb, ub]` below and above the set are also considered.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(1, 3), (5, 9)].to_interval_set();
-    /// assert_eq!(interval_set.difference(&4), interval_set);
-    /// assert_eq!(interval_set.difference(&5), [(1, 3), (6, 9)].to_interval_set());
-    /// assert_eq!(interval_set.difference(&7), [(1, 3), (5, 6), (8, 9)].to_interval_set());
+    /// let interval_set = [(0, 1), (5, 5), (10, 20)].to_interval_set();
+    /// assert_eq!(interval_set.first_gap_of_size_within(2 as u32, -10, 30), Some(Interval::new(-10, -1)));
+    /// assert_eq!(interval_set.first_gap_of_size_within(5 as u32, 0, 30), Some(Interval::new(21, 30)));
+    /// assert_eq!(interval_set.first_gap_of_size_within(5 as u32, 0, 20), None);
     /// ```
-    fn difference(&self, rhs: &Bound) -> IntervalSet<Bound> {
-        self.difference(&IntervalSet::singleton(rhs.clone()))
+    pub fn first_gap_of_size_within(
+        &self,
+        size: <Bound as Width>::Output,
+        lb: Bound,
+        ub: Bound,
+    ) -> Option<Interval<Bound>> {
+        self.invert_within(&IntervalSet::new(lb, ub))
+            .intervals
+            .into_iter()
+            .find(|gap| gap.size() >= size)
     }
-}
-
-impl<Bound: Width + Num> Difference for IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates the interval set containing all items in the left set,
-    /// but not in the right set.
-    /// The difference is not symmetric.
+    /// Counts the intervals of the set with at most `max_size` values, without allocating.
+    /// Many small intervals hint that [`IntervalSet::simplify`] or [`IntervalSet::clamp_count`] could pay off.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
-    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
-    /// assert_eq!(a.difference(&b), [(1, 1), (10, 11)].to_interval_set());
-    /// assert_eq!(b.difference(&a), [(4, 5), (7, 7), (12, 15)].to_interval_set());
+    /// let interval_set = [(0, 0), (2, 4), (10, 17), (20, 21)].to_interval_set();
+    /// assert_eq!(interval_set.count_small_intervals(2 as u32), 2);
+    /// assert_eq!(interval_set.count_small_intervals(0 as u32), 0);
     /// ```
-    fn difference(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.intersection(&rhs.complement())
+    pub fn count_small_intervals(&self, max_size: <Bound as Width>::Output) -> usize {
+        self.intervals
+            .iter()
+            .filter(|i| i.size() <= max_size)
+            .count()
     }
-}
-
-impl<Bound> SymmetricDifference<Bound> for IntervalSet<Bound>
-where
-    Bound: Width + Num + Clone,
-{
-    type Output = IntervalSet<Bound>;
 
-    /// If the value is in the interval set, this return the interval set without the value.
-    /// If the value is not in the interval set, this returns the interval set extended with the value.
+    /// Calculates the minimum number of bits needed to represent every value of the interval set, or `None` if it is empty.
+    /// When the set contains negative values, the sign bit is accounted for (two's complement), and at least one bit is always needed.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(1, 3), (5, 9)].to_interval_set();
-    /// assert_eq!(interval_set.symmetric_difference(&4), [(1, 9)].to_interval_set());
-    /// assert_eq!(interval_set.symmetric_difference(&4), interval_set.union(&4));
-
-    /// assert_eq!(interval_set.symmetric_difference(&5), [(1, 3), (6, 9)].to_interval_set());
-    /// assert_eq!(interval_set.symmetric_difference(&5), interval_set.difference(&5));
+    /// assert_eq!(IntervalSet::<u32>::new(0, 255).bounding_bits(), Some(8));
+    /// assert_eq!(IntervalSet::<u32>::new(3, 256).bounding_bits(), Some(9));
+    /// assert_eq!(IntervalSet::<i32>::new(-1, 1).bounding_bits(), Some(2));
+    /// assert_eq!([(-128, -100), (50, 127)].to_interval_set().bounding_bits(), Some(8));
+    /// assert_eq!(IntervalSet::<i32>::empty().bounding_bits(), None);
     /// ```
-    fn symmetric_difference(&self, rhs: &Bound) -> IntervalSet<Bound> {
-        self.symmetric_difference(&IntervalSet::singleton(rhs.clone()))
+    pub fn bounding_bits(&self) -> Option<u32> {
+        if self.is_empty() {
+            None
+        } else if self.lower() < Bound::zero() {
+            // In two's complement, `x < 0` needs as many bits as `-x - 1`, plus the sign bit.
+            let negative = bit_length(Bound::zero() - Bound::one() - self.lower());
+            let positive = if self.upper() < Bound::zero() {
+                0
+            } else {
+                bit_length(self.upper())
+            };
+            Some(negative.max(positive) + 1)
+        } else {
+            Some(bit_length(self.upper()).max(1))
+        }
     }
-}
-
-impl<Bound: Width + Num> SymmetricDifference for IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates the interval set containing that are in either the left set or the right set,
-    /// but not both.
+    /// Same as [`ShrinkLeft::shrink_left`] but borrows `self` when `lb` does not remove any value.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
-    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
-    /// let symmetric_difference = [(1, 1), (4, 5), (7, 7), (10, 15)].to_interval_set();
-    /// assert_eq!(a.symmetric_difference(&b), symmetric_difference);
-    /// assert_eq!(b.symmetric_difference(&a), symmetric_difference);
-    ///
-    /// assert_eq!(IntervalSet::union(&a.difference(&b), &b.difference(&a)), symmetric_difference);
+    /// # use std::borrow::Cow;
+    /// let interval_set = [(4, 5), (8, 8)].to_interval_set();
+    /// assert!(matches!(interval_set.shrink_left_cow(4), Cow::Borrowed(_)));
+    /// assert_eq!(interval_set.shrink_left_cow(5).into_owned(), [(5, 5), (8, 8)].to_interval_set());
     /// ```
-    fn symmetric_difference(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        let union = self.union(rhs);
-        let intersection = self.intersection(rhs);
-        union.difference(&intersection)
+    pub fn shrink_left_cow(&self, lb: Bound) -> Cow<'_, IntervalSet<Bound>> {
+        if self.is_empty() || lb <= self.lower() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self.shrink_left(lb))
+        }
     }
-}
 
-impl<Bound: Width + Num> Overlap for IntervalSet<Bound> {
-    /// Calculates whether two interval contain any shared values.
+    /// Same as [`ShrinkRight::shrink_right`] but borrows `self` when `ub` does not remove any value.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (7, 8)].to_interval_set();
-    /// let b = [(4, 6)].to_interval_set();
-    /// assert!(!a.overlap(&b));
-    /// assert!(!b.overlap(&a));
-    ///
-    /// let a = [(1, 3)].to_interval_set();
-    /// let b = [(3, 4), (8, 10)].to_interval_set();
-    /// assert!(a.overlap(&b));
-    /// assert!(b.overlap(&a));
+    /// # use std::borrow::Cow;
+    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
+    /// assert!(matches!(interval_set.shrink_right_cow(9), Cow::Borrowed(_)));
+    /// assert_eq!(interval_set.shrink_right_cow(7).into_owned(), [(3, 3), (7, 7)].to_interval_set());
     /// ```
-    fn overlap(&self, rhs: &IntervalSet<Bound>) -> bool {
-        let a = &mut self.intervals.iter().cloned().peekable();
-        let b = &mut rhs.intervals.iter().cloned().peekable();
-        advance_to_first_overlapping(a, b)
+    pub fn shrink_right_cow(&self, ub: Bound) -> Cow<'_, IntervalSet<Bound>> {
+        if self.is_empty() || ub >= self.upper() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self.shrink_right(ub))
+        }
     }
-}
 
-impl<Bound: Width + Num> Overlap<Bound> for IntervalSet<Bound> {
-    /// Calculates whether a value is included in the interval set.
-    /// This returns the same result as the [`IntervalSet::contains`]
+    /// Same as [`ShrinkLeft::shrink_left`] with an optional lower bound, an empty optional leaves the set unchanged.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
-    /// assert!(interval_set.overlap(&3));
-    /// assert!(interval_set.overlap(&8));
-    /// assert!(interval_set.overlap(&9));
-    ///
-    /// assert!(!interval_set.overlap(&1));
-    /// assert!(!interval_set.overlap(&7));
-    /// assert!(!interval_set.overlap(&10));
+    /// let interval_set = [(4, 5), (8, 8)].to_interval_set();
+    /// assert_eq!(interval_set.shrink_left_opt(Optional::singleton(5)), [(5, 5), (8, 8)].to_interval_set());
+    /// assert_eq!(interval_set.shrink_left_opt(Optional::empty()), interval_set);
     /// ```
-    fn overlap(&self, value: &Bound) -> bool {
-        if let Some((l, u)) = self.find_interval(value) {
-            l == u
-        } else {
-            false
+    pub fn shrink_left_opt(&self, lb: Optional<Bound>) -> IntervalSet<Bound> {
+        match lb.as_ref() {
+            Some(lb) => self.shrink_left(lb.clone()),
+            None => self.clone(),
         }
     }
-}
 
-impl<Bound: Width + Num> Overlap<Optional<Bound>> for IntervalSet<Bound> {
-    /// Calculates whether an optional value is included in the interval set.
-    /// If the optional empty, this returns false.
-    /// This returns the same result as the [`IntervalSet::contains`]
+    /// Same as [`ShrinkRight::shrink_right`] with an optional upper bound, an empty optional leaves the set unchanged.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
-    /// assert!(interval_set.overlap(&Optional::singleton(3)));
-    /// assert!(interval_set.overlap(&Optional::singleton(9)));
-    ///
-    /// assert!(!interval_set.overlap(&Optional::singleton(1)));
-    /// assert!(!interval_set.overlap(&Optional::singleton(10)));
-    ///
-    /// assert!(!interval_set.overlap(&Optional::empty()));
+    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
+    /// assert_eq!(interval_set.shrink_right_opt(Optional::singleton(7)), [(3, 3), (7, 7)].to_interval_set());
+    /// assert_eq!(interval_set.shrink_right_opt(Optional::empty()), interval_set);
     /// ```
-    fn overlap(&self, value: &Optional<Bound>) -> bool {
-        value.as_ref().map_or(false, |b| self.overlap(b))
-    }
-}
-
-macro_rules! primitive_interval_set_overlap
-{
-  ( $( $source:ty ),* ) =>
-  {$(
-    impl Overlap<IntervalSet<$source>> for $source {
-      #[doc = concat!(
-        r#"
-        Calculates whether a value is included in an interval set.
-        ```
-        # use interval::prelude::*;
-        let interval_set: IntervalSet<"#, stringify!($source), r#"> = [(3, 5), (8, 9)].to_interval_set();
-        assert!((3 as "#, stringify!($source), r#").overlap(&interval_set));
-        assert!((8 as "#, stringify!($source), r#").overlap(&interval_set));
-        assert!((9 as "#, stringify!($source), r#").overlap(&interval_set));
-        ///
-        assert!(!(1 as "#, stringify!($source), r#").overlap(&interval_set));
-        assert!(!(7 as "#, stringify!($source), r#").overlap(&interval_set));
-        assert!(!(10 as "#, stringify!($source), r#").overlap(&interval_set));
-        ```
-        "#
-      )]
-      fn overlap(&self, other: &IntervalSet<$source>) -> bool {
-        other.overlap(self)
-      }
+    pub fn shrink_right_opt(&self, ub: Optional<Bound>) -> IntervalSet<Bound> {
+        match ub.as_ref() {
+            Some(ub) => self.shrink_right(ub.clone()),
+            None => self.clone(),
+        }
     }
-  )*}
-}
-
-primitive_interval_set_overlap!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
 
-impl<Bound: Width + Num> Disjoint for IntervalSet<Bound> {
-    /// Calculates whether two interval do *not* contain any shared values.
+    /// Constructs an interval set from a bitmask where the bit `i` represents the value `offset + i`.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 3), (7, 8)].to_interval_set();
-    /// let b = [(4, 6)].to_interval_set();
-    /// assert!(a.is_disjoint(&b));
-    /// assert!(b.is_disjoint(&a));
-    ///
-    /// let a = [(1, 3)].to_interval_set();
-    /// let b = [(3, 4), (8, 10)].to_interval_set();
-    /// assert!(!a.is_disjoint(&b));
-    /// assert!(!b.is_disjoint(&a));
+    /// assert_eq!(IntervalSet::from_bits(0b1110_0011, 0), [(0, 1), (5, 7)].to_interval_set());
+    /// assert_eq!(IntervalSet::from_bits(0b1110_0011, 10), [(10, 11), (15, 17)].to_interval_set());
+    /// assert_eq!(IntervalSet::from_bits(u64::max_value(), -10), IntervalSet::new(-10, 53));
+    /// assert!(IntervalSet::<u32>::from_bits(0, 0).is_empty());
     /// ```
-    fn is_disjoint(&self, rhs: &IntervalSet<Bound>) -> bool {
-        !self.overlap(rhs)
+    /// Panics if the value of a set bit is not between [`Width::min_value`] and [`Width::max_value`],
+    /// for example `offset + 63` when the last bit is set and `offset + 63` overflows.
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// let _ = IntervalSet::<i8>::from_bits(1 << 40, 100); // panics!
+    /// ```
+    pub fn from_bits(mask: u64, offset: Bound) -> IntervalSet<Bound>
+    where
+        Bound: NumCast + CheckedAdd,
+    {
+        let at = |bit: u32| {
+            <Bound as NumCast>::from(bit)
+                .and_then(|bit| offset.checked_add(&bit))
+                .filter(|value| {
+                    <Bound as Width>::min_value() <= *value && *value <= <Bound as Width>::max_value()
+                })
+                .expect("`from_bits` expects the values of the set bits to be representable by the bound.")
+        };
+        let mut res = IntervalSet::empty();
+        let mut bit = 0;
+        while bit < 64 {
+            let rest = mask >> bit;
+            if rest == 0 {
+                break;
+            }
+            let start = bit + rest.trailing_zeros();
+            let end = start + (mask >> start).trailing_ones();
+            res.push(Interval::new(at(start), at(end - 1)));
+            bit = end;
+        }
+        res
     }
-}
 
-impl<Bound: Width + Num> ShrinkLeft for IntervalSet<Bound>
-where
-    <Bound as Width>::Output: Clone,
-{
-    /// Updates the lower bound of an interval set to be greater than or equal to a value.
+    /// Converts an interval set to a bitmask where the bit `i` represents the value `offset + i`.
+    /// Returns `None` if a value of the set is not in `offset..offset+64`.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(4, 5), (8, 8)].to_interval_set();
-    /// assert_eq!(interval_set.shrink_left(2), interval_set);
-    /// assert_eq!(interval_set.shrink_left(4), interval_set);
-    /// assert_eq!(interval_set.shrink_left(5), [(5, 5), (8, 8)].to_interval_set());
-    /// assert_eq!(interval_set.shrink_left(7), IntervalSet::singleton(8));
-    /// assert_eq!(interval_set.shrink_left(8), IntervalSet::singleton(8));
-    /// assert_eq!(interval_set.shrink_left(9), IntervalSet::empty());
+    /// assert_eq!([(0, 1), (5, 7)].to_interval_set().to_bits(0), Some(0b1110_0011));
+    /// assert_eq!([(10, 11), (15, 17)].to_interval_set().to_bits(10), Some(0b1110_0011));
+    /// assert_eq!(IntervalSet::<u32>::empty().to_bits(0), Some(0));
+    /// assert_eq!([(5, 7)].to_interval_set().to_bits(6), None);
+    /// assert_eq!([(5, 70)].to_interval_set().to_bits(0), None);
     /// ```
-    fn shrink_left(&self, lb: Bound) -> IntervalSet<Bound> {
-        if let Some((left, _)) = self.find_interval(&lb) {
-            let mut res = IntervalSet::empty();
-            if self.intervals[left].upper() >= lb {
-                res.push(Interval::new(lb, self.intervals[left].upper()));
+    pub fn to_bits(&self, offset: Bound) -> Option<u64>
+    where
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        let mut mask = 0u64;
+        for interval in &self.intervals {
+            if interval.lower() < offset {
+                return None;
             }
-            for i in (left + 1)..self.intervals.len() {
-                res.push(self.intervals[i].clone());
+            // The width from `offset` is one more than the bit index.
+            let start = Bound::width(&offset, &interval.lower()).to_u64()?;
+            let end = Bound::width(&offset, &interval.upper()).to_u64()?;
+            if end > 64 {
+                return None;
             }
-            res
-        } else if self.is_empty() || lb > self.back().upper() {
-            IntervalSet::empty()
-        } else {
-            self.clone()
+            mask |= (u64::MAX >> (64 - (end - start + 1))) << (start - 1);
         }
+        Some(mask)
     }
-}
 
-impl<Bound: Width + Num> ShrinkRight for IntervalSet<Bound>
-where
-    <Bound as Width>::Output: Clone,
-{
-    /// Updates the upper bound of an interval set to be less than or equal to a value.
+    /// Iterates over every value contained in the set, in increasing order.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
-    /// assert_eq!(interval_set.shrink_right(9), interval_set);
-    /// assert_eq!(interval_set.shrink_right(8), interval_set);
-    /// assert_eq!(interval_set.shrink_right(7), [(3, 3), (7, 7)].to_interval_set());
-    /// assert_eq!(interval_set.shrink_right(6), IntervalSet::singleton(3));
-    /// assert_eq!(interval_set.shrink_right(3), IntervalSet::singleton(3));
-    /// assert_eq!(interval_set.shrink_right(2), IntervalSet::empty());
+    /// let interval_set = [(1, 3), (6, 6)].to_interval_set();
+    /// let values: Vec<_> = interval_set.iter_values().collect();
+    /// assert_eq!(values, vec![1, 2, 3, 6]);
     /// ```
-    fn shrink_right(&self, ub: Bound) -> IntervalSet<Bound> {
-        if let Some((_, right)) = self.find_interval(&ub) {
-            let mut res = IntervalSet::empty();
-            for i in 0..right {
-                res.push(self.intervals[i].clone());
-            }
-            if self.intervals[right].lower() <= ub {
-                res.push(Interval::new(self.intervals[right].lower(), ub));
-            }
-            res
-        } else if self.is_empty() || ub < self.front().lower() {
-            IntervalSet::empty()
-        } else {
-            self.clone()
-        }
+    pub fn iter_values(&self) -> impl Iterator<Item = Bound> + '_ {
+        self.intervals
+            .iter()
+            .flat_map(|i| interval_values(i.lower(), i.upper()))
     }
-}
 
-impl<Bound: Width + Num> Subset for IntervalSet<Bound> {
-    /// Calculates whether one interval set is contained in another.
-    /// The empty interval set is a subset of everything.
+    /// Iterates over the values of the set within `[lb, ub]`, in increasing order.
+    /// The starting interval is found by binary search, and no value is produced when `lb > ub`.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
-    /// assert!(interval_set.is_subset(&[(3, 8)].to_interval_set()));
-    /// assert!(interval_set.is_subset(&[(3, 4), (7, 9)].to_interval_set()));
-    /// assert!(interval_set.is_subset(&interval_set));
-    ///
-    /// assert!(!interval_set.is_subset(&[(3, 3)].to_interval_set()));
-    /// assert!(!interval_set.is_subset(&[(7, 9)].to_interval_set()));
-    /// assert!(!interval_set.is_subset(&[(3, 3), (8, 9)].to_interval_set()));
-    ///
-    /// assert!(IntervalSet::<usize>::empty().is_subset(&IntervalSet::empty()));
-    /// assert!(IntervalSet::empty().is_subset(&interval_set));
+    /// let interval_set = [(0, 5), (10, 15)].to_interval_set();
+    /// let values: Vec<_> = interval_set.iter_values_in(3, 11).collect();
+    /// assert_eq!(values, vec![3, 4, 5, 10, 11]);
     /// ```
-    fn is_subset(&self, other: &IntervalSet<Bound>) -> bool {
-        if self.is_empty() {
-            true
-        } else if self.size() > other.size() || !self.span().is_subset(&other.span()) {
-            false
-        } else {
-            let mut left = 0;
-            let right = other.intervals.len() - 1;
-            for interval in &self.intervals {
-                let (l, r) = other.find_interval_between(&interval.lower(), left, right);
-                if l == r && interval.is_subset(&other.intervals[l]) {
-                    left = l;
-                } else {
-                    return false;
-                }
-            }
-            true
-        }
+    pub fn iter_values_in(&self, lb: Bound, ub: Bound) -> impl Iterator<Item = Bound> + '_ {
+        let start = self.intervals.partition_point(|i| i.upper() < lb);
+        let last = ub.clone();
+        self.intervals[start..]
+            .iter()
+            .take_while(move |i| i.lower() <= last)
+            .flat_map(move |i| {
+                interval_values(max(i.lower(), lb.clone()), min(i.upper(), ub.clone()))
+            })
     }
-}
 
-impl<Bound: Width + Num> ProperSubset for IntervalSet<Bound> {
-    /// Calculates whether one interval set is contained in another,
-    /// but they are not equal.
-    /// The empty interval set is a proper subset of everything, except itself.
+    /// Keeps the values of the set satisfying `f`, consecutive kept values forming a single interval.
+    /// `f` is called on every value of the set, in increasing order: this is intended for sets of modest size.
     /// ```
     /// # use interval::prelude::*;
-    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
-    /// assert!(interval_set.is_proper_subset(&[(3, 8)].to_interval_set()));
-    /// assert!(interval_set.is_proper_subset(&[(3, 4), (7, 9)].to_interval_set()));
-    ///
-    /// assert!(!interval_set.is_proper_subset(&interval_set));
-    /// assert!(!interval_set.is_proper_subset(&[(3, 3)].to_interval_set()));
-    /// assert!(!interval_set.is_proper_subset(&[(7, 9)].to_interval_set()));
-    /// assert!(!interval_set.is_proper_subset(&[(3, 3), (8, 9)].to_interval_set()));
-    ///
-    /// assert!(IntervalSet::empty().is_proper_subset(&interval_set));
-    /// assert!(!IntervalSet::<usize>::empty().is_proper_subset(&IntervalSet::empty()));
+    /// let interval_set = [(0, 5)].to_interval_set();
+    /// let evens = interval_set.retain_values(|v| v % 2 == 0);
+    /// assert_eq!(evens, [(0, 0), (2, 2), (4, 4)].to_interval_set());
     /// ```
-    fn is_proper_subset(&self, other: &IntervalSet<Bound>) -> bool {
-        self.is_subset(other) && self.size() != other.size()
+    pub fn retain_values<F>(&self, f: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Bound) -> bool,
+    {
+        let mut res = IntervalSet::empty();
+        res.extend_at_back(self.iter_values().filter(|v| f(v)).map(Interval::singleton));
+        res
     }
-}
-
-forward_all_binop!(impl<Bound: +Num+Width> Add for IntervalSet<Bound>, add);
-
-impl<'a, 'b, Bound: Num + Width> Add<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates all values that could result in the addition of two items from each interval set.
-    /// ```
-    /// # use interval::prelude::*;
-    /// let a = [(1, 2), (5, 6)].to_interval_set();
-    /// let b = [(1, 1), (4, 5)].to_interval_set();
-    /// assert_eq!(a + b, [(2, 3), (5, 7), (9, 11)].to_interval_set());
-    /// ```
-    /// This method preserves empty interval sets.
+    /// Splits the set into the values satisfying `f` and the values not satisfying it.
+    /// The two sets are disjoint and their union is the original set.
+    /// `f` is called once on every value of the set, in increasing order: this takes time linear in the size of the set.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 1), (4, 5)].to_interval_set();
-    /// let b = IntervalSet::empty();
-    /// assert!((a + b).is_empty());
+    /// let interval_set = [(0, 5)].to_interval_set();
+    /// let (evens, odds) = interval_set.partition(|v| v % 2 == 0);
+    /// assert_eq!(evens, [(0, 0), (2, 2), (4, 4)].to_interval_set());
+    /// assert_eq!(odds, [(1, 1), (3, 3), (5, 5)].to_interval_set());
     /// ```
-    fn add(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.for_all_pairs(other, |i, j| i + j)
+    pub fn partition<F>(&self, f: F) -> (IntervalSet<Bound>, IntervalSet<Bound>)
+    where
+        F: Fn(&Bound) -> bool,
+    {
+        let mut matching = IntervalSet::empty();
+        let mut others = IntervalSet::empty();
+        for v in self.iter_values() {
+            let res = if f(&v) { &mut matching } else { &mut others };
+            res.join_or_push(Interval::singleton(v));
+        }
+        (matching, others)
     }
-}
-
-forward_all_binop!(impl<Bound: +Num+Width+Clone> Add for IntervalSet<Bound>, add, Bound);
 
-impl<'a, 'b, Bound: Num + Width + Clone> Add<&'b Bound> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
-
-    /// Adds a constant to an interval set.
+    /// Iterates over the intervals of the set as inclusive ranges.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!([(3, 3), (7, 8)].to_interval_set() + 2, [(5, 5), (9, 10)].to_interval_set());
-    /// ```
-    /// This method preserves empty interval sets.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert!((IntervalSet::empty() + 4).is_empty());
-    /// ```
-    /// It is not possible to add an interval set to a constant.
-    /// ```compile_fail
-    /// # use interval::prelude::*;
-    /// let _ = 4 + IntervalSet::new(5, 9); // doesn't compile
+    /// let interval_set = [(1, 3), (7, 7)].to_interval_set();
+    /// assert_eq!(interval_set.as_ranges().collect::<Vec<_>>(), vec![1..=3, 7..=7]);
     /// ```
-    fn add(self, other: &Bound) -> IntervalSet<Bound> {
-        self.stable_map(|x| x + other.clone())
-    }
-}
-
-forward_all_binop!(impl<Bound: +Num+Width> Sub for IntervalSet<Bound>, sub);
-
-impl<'a, 'b, Bound: Num + Width> Sub<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
-
-    fn sub(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.for_all_pairs(other, |i, j| i - j)
+    pub fn as_ranges(&self) -> impl Iterator<Item = RangeInclusive<Bound>> + '_ {
+        self.intervals.iter().map(|i| i.lower()..=i.upper())
     }
-}
 
-forward_all_binop!(impl<Bound: +Num+Width+Clone> Sub for IntervalSet<Bound>, sub, Bound);
-
-impl<'a, 'b, Bound: Num + Width + Clone> Sub<&'b Bound> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
-
-    /// Subtracts a constant from an interval set.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert_eq!([(3, 3), (7, 8)].to_interval_set() - 2, [(1, 1), (5, 6)].to_interval_set());
-    /// ```
-    /// This method preserves empty interval sets.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert!((IntervalSet::empty() - 4).is_empty());
+    /// Iterates over every pair `(x, y)` with `x` in `self` and `y` in `other`, ordered by `x` then by `y`.
+    /// The pairs are generated lazily but their number is `self.size() * other.size()`: this is intended for small sets.
     /// ```
-    /// It is not possible to substract an interval set from a constant.
-    /// ```compile_fail
     /// # use interval::prelude::*;
-    /// let _ = 10 - IntervalSet::new(5, 9); // doesn't compile
+    /// let a = [(1, 2)].to_interval_set();
+    /// let b = [(5, 5), (7, 7)].to_interval_set();
+    /// let pairs: Vec<_> = a.cartesian_pairs(&b).collect();
+    /// assert_eq!(pairs, vec![(1, 5), (1, 7), (2, 5), (2, 7)]);
     /// ```
-    fn sub(self, other: &Bound) -> IntervalSet<Bound> {
-        self.stable_map(|x| x - other.clone())
+    pub fn cartesian_pairs<'a>(
+        &'a self,
+        other: &'a IntervalSet<Bound>,
+    ) -> impl Iterator<Item = (Bound, Bound)> + 'a {
+        self.iter_values()
+            .flat_map(move |x| other.iter_values().map(move |y| (x.clone(), y)))
     }
-}
-
-forward_all_binop!(impl<Bound: +Num+Width> Mul for IntervalSet<Bound>, mul);
-
-impl<'a, 'b, Bound: Num + Width> Mul<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
 
-    /// Calculates all values that could result in the multiplication of two items from each interval set.
-    /// Caution: the resulting interval set is an over-approxmation for the same reason as [`Interval::mul`](../interval/struct.Interval.html#method.mul-3).
+    /// Iterates over every interval of `len` values included in a single interval of the set, sliding by one value.
+    /// The windows never span a gap between two intervals, and no window is produced when `len` is zero.
     /// ```
     /// # use interval::prelude::*;
-    /// let a = [(1, 2), (5, 6)].to_interval_set();
-    /// let b = [(0, 0), (3, 4)].to_interval_set();
-    /// assert_eq!(a * b, [(0, 0), (3, 8), (15, 24)].to_interval_set());
+    /// let interval_set = [(0, 4), (7, 8), (10, 12)].to_interval_set();
+    /// let windows: Vec<_> = interval_set.windows(3 as u32).collect();
+    /// assert_eq!(windows, vec![Interval::new(0, 2), Interval::new(1, 3), Interval::new(2, 4), Interval::new(10, 12)]);
     /// ```
-    /// This method preserves empty interval sets.
+    pub fn windows(
+        &self,
+        len: <Bound as Width>::Output,
+    ) -> impl Iterator<Item = Interval<Bound>> + '_
+    where
+        Bound: NumCast,
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        let min_size = len.clone();
+        self.intervals
+            .iter()
+            .filter(move |i| !min_size.is_zero() && i.size() >= min_size)
+            .flat_map(move |i| {
+                let span = len.clone() - <Bound as Width>::Output::one();
+                let last_start = add_width(i.lower(), i.size() - len.clone());
+                interval_values(i.lower(), last_start)
+                    .map(move |start| Interval::new(start.clone(), add_width(start, span.clone())))
+            })
+    }
+
+    /// Renders the coverage of the set over `[lb, ub]` as one character per value, `'X'` for a contained value and `'.'` otherwise.
+    /// The rendering is truncated to its first 4096 characters to avoid huge allocations.
     /// ```
     /// # use interval::prelude::*;
-    /// assert!((IntervalSet::empty() * [(0, 0), (3, 4)].to_interval_set()).is_empty());
+    /// let interval_set = [(1, 3), (6, 6)].to_interval_set();
+    /// assert_eq!(interval_set.ascii_bar(0, 7), ".XXX..X.");
     /// ```
-    fn mul(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.for_all_pairs(other, |i, j| i * j)
+    /// Panics if `lb > ub`.
+    pub fn ascii_bar(&self, lb: Bound, ub: Bound) -> String
+    where
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        assert!(lb <= ub, "Cannot render an ASCII bar over an empty window.");
+        let width = Bound::width(&lb, &ub)
+            .to_usize()
+            .map_or(ASCII_BAR_MAX_WIDTH, |w| w.min(ASCII_BAR_MAX_WIDTH));
+        let mut bar = String::with_capacity(width);
+        let mut idx = self.intervals.partition_point(|i| i.upper() < lb);
+        let mut value = lb;
+        for pos in 0..width {
+            while idx < self.intervals.len() && self.intervals[idx].upper() < value {
+                idx += 1;
+            }
+            let contained = idx < self.intervals.len() && self.intervals[idx].lower() <= value;
+            bar.push(if contained { 'X' } else { '.' });
+            // Do not step past `ub` which might be the largest value of `Bound`.
+            if pos + 1 < width {
+                value = value + Bound::one();
+            }
+        }
+        bar
     }
-}
 
-forward_all_binop!(impl<Bound: +Num+Width+Clone> Mul for IntervalSet<Bound>, mul, Bound);
-
-impl<'a, 'b, Bound: Num + Width + Clone> Mul<&'b Bound> for &'a IntervalSet<Bound> {
-    type Output = IntervalSet<Bound>;
-
-    /// Multiplies an interval set by a constant.
-    /// Caution: the resulting interval set is an over-approxmation for the same reason as [`Interval::mul`](../interval/struct.Interval.html#method.mul-7).
+    /// Materializes the coverage of the set over `[lb, ub]` as one boolean per value, `true` for a contained value.
+    /// The vector has `ub - lb + 1` elements, so this is intended for narrow windows: a wide window allocates one byte per value.
+    /// An empty window (`lb > ub`) gives an empty vector.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!([(1, 2), (5, 6)].to_interval_set() * 2, [(2, 4), (10, 12)].to_interval_set());
+    /// let interval_set = [(1, 3), (6, 6)].to_interval_set();
+    /// assert_eq!(interval_set.to_coverage_vec(0, 7), vec![false, true, true, true, false, false, true, false]);
+    /// assert!(interval_set.to_coverage_vec(7, 0).is_empty());
     /// ```
-    /// This method preserves empty interval sets.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert!((IntervalSet::empty() * 11).is_empty());
+    pub fn to_coverage_vec(&self, lb: Bound, ub: Bound) -> Vec<bool>
+    where
+        <Bound as Width>::Output: ToPrimitive,
+    {
+        if lb > ub {
+            return Vec::new();
+        }
+        let position = |value: &Bound| {
+            (Bound::width(&lb, value) - <Bound as Width>::Output::one())
+                .to_usize()
+                .expect("The window is too wide to be materialized.")
+        };
+        let mut coverage = vec![false; position(&ub) + 1];
+        let start = self.intervals.partition_point(|i| i.upper() < lb);
+        for i in self.intervals[start..]
+            .iter()
+            .take_while(|i| i.lower() <= ub)
+        {
+            let first = position(&max(i.lower(), lb.clone()));
+            let last = position(&min(i.upper(), ub.clone()));
+            coverage[first..=last].fill(true);
+        }
+        coverage
+    }
+
+    /// Removes the values of an interval from the interval set.
+    /// The intervals partially covered by `i` are cut, and the ones fully covered are removed.
     /// ```
-    /// It is not possible to multiply a constant by an interval set.
-    /// ```compile_fail
     /// # use interval::prelude::*;
-    /// let _ = 4 * IntervalSet::new(5, 9); // doesn't compile
+    /// let mut interval_set = [(1, 9)].to_interval_set();
+    /// interval_set.remove_interval(&Interval::new(4, 5));
+    /// assert_eq!(interval_set, [(1, 3), (6, 9)].to_interval_set());
+    /// interval_set.remove_interval(&Interval::new(3, 7));
+    /// assert_eq!(interval_set, [(1, 2), (8, 9)].to_interval_set());
+    /// interval_set.remove_interval(&Interval::new(4, 6));
+    /// assert_eq!(interval_set, [(1, 2), (8, 9)].to_interval_set());
     /// ```
-    fn mul(self, other: &Bound) -> IntervalSet<Bound> {
-        if self.is_empty() {
-            IntervalSet::empty()
-        } else if other == &Bound::zero() {
-            IntervalSet::singleton(Bound::zero())
-        } else if other == &Bound::one() {
-            self.clone()
-        } else {
-            self.map(|i| i * other.clone())
+    pub fn remove_interval(&mut self, i: &Interval<Bound>) {
+        if i.is_empty() {
+            return;
         }
+        let start = self.intervals.partition_point(|x| x.upper() < i.lower());
+        let end = self.intervals.partition_point(|x| x.lower() <= i.upper());
+        if start >= end {
+            return;
+        }
+        let mut pieces = Vec::with_capacity(2);
+        let first = &self.intervals[start];
+        if first.lower() < i.lower() {
+            pieces.push(Interval::new(first.lower(), i.lower() - Bound::one()));
+        }
+        let last = &self.intervals[end - 1];
+        if last.upper() > i.upper() {
+            pieces.push(Interval::new(i.upper() + Bound::one(), last.upper()));
+        }
+        let added = pieces
+            .iter()
+            .fold(<Bound as Width>::Output::zero(), |size, x| size + x.size());
+        let removed = self
+            .intervals
+            .splice(start..end, pieces)
+            .fold(<Bound as Width>::Output::zero(), |size, x| size + x.size());
+        self.size = self.size.clone() - removed + added;
     }
-}
 
-pub trait ToIntervalSet<Bound>
-where
-    Bound: Width,
-{
-    /// Converts a value to an interval set.
-    /// For example,
+    /// Replaces the interval at position `index` by `new` and returns the replaced interval, or `None` if `index` is out of range.
+    /// The set is sorted and merged again, as `new` may overlap or be adjacent to other intervals, and an empty `new` removes the interval.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!((3, 4).to_interval_set(), IntervalSet::new(3, 4));
-    /// assert_eq!([(2, 5), (7, 8)].to_interval_set(), IntervalSet::union(&IntervalSet::new(2, 5), &IntervalSet::new(7, 8)));
+    /// let mut interval_set = [(1, 2), (6, 7), (12, 15)].to_interval_set();
+    /// assert_eq!(interval_set.replace_interval(1, Interval::new(3, 9)), Some(Interval::new(6, 7)));
+    /// assert_eq!(interval_set, [(1, 9), (12, 15)].to_interval_set());
+    /// assert_eq!(interval_set.replace_interval(2, Interval::new(0, 0)), None);
+    /// assert_eq!(interval_set, [(1, 9), (12, 15)].to_interval_set());
     /// ```
-    fn to_interval_set(self) -> IntervalSet<Bound>;
-}
+    pub fn replace_interval(
+        &mut self,
+        index: usize,
+        new: Interval<Bound>,
+    ) -> Option<Interval<Bound>> {
+        let old = self.intervals.get(index)?.clone();
+        self.set_bounds(|intervals| intervals[index] = new);
+        Some(old)
+    }
 
-impl<Bound: Width + Num> ToIntervalSet<Bound> for (Bound, Bound) {
-    /// Converts a tuple to an interval set using the first element as the lower bound
-    /// and second element as the upper bound.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert_eq!((2, 6).to_interval_set(), IntervalSet::new(2, 6));
+    /// Moves the lower bound of the set down by `n`, only extending the first interval.
+    /// The lower bound saturates at [`Width::min_value`].
     /// ```
-    /// The first and second elements need the same type.
-    /// ```compile_fail
     /// # use interval::prelude::*;
-    /// let _ = (8 as u8, 9 as i8).to_interval_set(); // doesn't compile
+    /// let interval_set = [(5, 6), (10, 11)].to_interval_set();
+    /// assert_eq!(interval_set.grow_left(7), [(-2, 6), (10, 11)].to_interval_set());
+    /// assert_eq!(IntervalSet::new(3u8, 4).grow_left(10), IntervalSet::new(0, 4));
     /// ```
-    fn to_interval_set(self) -> IntervalSet<Bound> {
-        [self].to_interval_set()
+    /// Panics if `n` is negative.
+    pub fn grow_left(&self, n: Bound) -> IntervalSet<Bound>
+    where
+        Bound: CheckedSub,
+    {
+        assert!(
+            n >= Bound::zero(),
+            "Cannot grow an interval set by a negative amount."
+        );
+        let mut res = self.clone();
+        if let Some(front) = res.intervals.first_mut() {
+            let min = <Bound as Width>::min_value();
+            let lower = match front.lower().checked_sub(&n) {
+                Some(lower) if lower >= min => lower,
+                _ => min,
+            };
+            let grown = Interval::new(lower, front.upper());
+            res.size = res.size.clone() - front.size() + grown.size();
+            *front = grown;
+        }
+        res
     }
-}
 
-impl<Bound> ToIntervalSet<Bound> for Vec<(Bound, Bound)>
-where
-    Bound: Width + Num,
-{
-    /// Converts a vector of intervals to an interval set.
+    /// Moves the upper bound of the set up by `n`, only extending the last interval.
+    /// The upper bound saturates at [`Width::max_value`].
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!(vec![(2, 5)].to_interval_set().interval_count(), 1);
-    /// assert_eq!(vec![(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
-    /// assert!(Vec::<(usize, usize)>::new().to_interval_set().is_empty());
+    /// let interval_set = [(5, 6), (10, 11)].to_interval_set();
+    /// assert_eq!(interval_set.grow_right(7), [(5, 6), (10, 18)].to_interval_set());
+    /// assert_eq!(IntervalSet::new(250u8, 251).grow_right(10), IntervalSet::new(250, 254));
     /// ```
-    fn to_interval_set(self) -> IntervalSet<Bound> {
-        let mut intervals = IntervalSet::empty();
-        let mut to_add: Vec<_> = self.into_iter().map(|i| i.to_interval()).collect();
-        to_add.sort_unstable_by_key(|i| i.lower());
-        intervals.extend_at_back(to_add);
-        intervals
+    /// Panics if `n` is negative.
+    pub fn grow_right(&self, n: Bound) -> IntervalSet<Bound>
+    where
+        Bound: CheckedAdd,
+    {
+        assert!(
+            n >= Bound::zero(),
+            "Cannot grow an interval set by a negative amount."
+        );
+        let mut res = self.clone();
+        if let Some(back) = res.intervals.last_mut() {
+            let max = <Bound as Width>::max_value();
+            let upper = match back.upper().checked_add(&n) {
+                Some(upper) if upper <= max => upper,
+                _ => max,
+            };
+            let grown = Interval::new(back.lower(), upper);
+            res.size = res.size.clone() - back.size() + grown.size();
+            *back = grown;
+        }
+        res
     }
-}
 
-impl<Bound> ToIntervalSet<Bound> for &[(Bound, Bound)]
-where
-    Bound: Width + Num + Copy,
-{
-    /// Converts an array to an interval set.
+    /// Extends the interval set to include `value` by growing the nearest interval up to `value`, instead of adding an isolated value.
+    /// When `value` is in a gap at the same distance of the two surrounding intervals, the interval on the left is grown.
+    /// Intervals touching after the growth are merged, and nothing is done if `value` is already in the set.
     /// ```
     /// # use interval::prelude::*;
-    /// assert_eq!([(2, 5)].to_interval_set().interval_count(), 1);
-    /// assert_eq!([(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
-    /// assert!(<&[(usize, usize)]>::default().to_interval_set().is_empty());
+    /// let mut interval_set = [(3, 4), (10, 12)].to_interval_set();
+    /// interval_set.grow_to_include(0);
+    /// assert_eq!(interval_set, [(0, 4), (10, 12)].to_interval_set());
+    /// interval_set.grow_to_include(15);
+    /// assert_eq!(interval_set, [(0, 4), (10, 15)].to_interval_set());
+    /// interval_set.grow_to_include(8);
+    /// assert_eq!(interval_set, [(0, 4), (8, 15)].to_interval_set());
+    /// interval_set.grow_to_include(6);
+    /// assert_eq!(interval_set, [(0, 6), (8, 15)].to_interval_set());
+    /// interval_set.grow_to_include(7);
+    /// assert_eq!(interval_set, [(0, 15)].to_interval_set());
     /// ```
-    fn to_interval_set(self) -> IntervalSet<Bound> {
-        self.to_vec().to_interval_set()
-    }
-}
-
-impl<Bound, const N: usize> ToIntervalSet<Bound> for [(Bound, Bound); N]
-where
-    Bound: Width + Num + Clone,
-{
-    /// Converts a fixed-length array to an interval set.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert_eq!([(2, 5)].to_interval_set().interval_count(), 1);
-    /// assert_eq!([(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
-    /// assert!(([] as [(usize, usize); 0]).to_interval_set().is_empty());
-    /// ```
-    fn to_interval_set(self) -> IntervalSet<Bound> {
-        self.to_vec().to_interval_set()
+    pub fn grow_to_include(&mut self, value: Bound) {
+        if self.is_empty() {
+            *self = IntervalSet::singleton(value);
+            return;
+        }
+        let (idx, grown) = match self.find_interval(&value) {
+            Some((left, right)) if left == right => return,
+            Some((left, right)) => {
+                let before = &self.intervals[left];
+                let after = &self.intervals[right];
+                if Bound::width(&before.upper(), &value) <= Bound::width(&value, &after.lower()) {
+                    (left, Interval::new(before.lower(), value))
+                } else {
+                    (right, Interval::new(value, after.upper()))
+                }
+            }
+            None if value < self.lower() => (0, Interval::new(value, self.front().upper())),
+            None => (self.back_idx(), Interval::new(self.back().lower(), value)),
+        };
+        self.size = self.size.clone() - self.intervals[idx].size() + grown.size();
+        self.intervals[idx] = grown;
+        // The grown interval does not overlap its neighbours, so merging them does not change the size.
+        if idx + 1 < self.intervals.len()
+            && joinable(&self.intervals[idx], &self.intervals[idx + 1])
+        {
+            let next = self.intervals.remove(idx + 1);
+            self.intervals[idx] = self.intervals[idx].hull(&next);
+        }
+        if idx > 0 && joinable(&self.intervals[idx - 1], &self.intervals[idx]) {
+            let current = self.intervals.remove(idx);
+            self.intervals[idx - 1] = self.intervals[idx - 1].hull(&current);
+        }
     }
-}
 
-impl<Bound: Display + Width + Num> Display for IntervalSet<Bound>
-where
-    <Bound as Width>::Output: Display,
-{
-    /// Formats an interval set.
-    /// Empty interval sets are displayed as the empty set "{}".
-    /// Single intervals are displayed as the isolated interval.
-    /// Combined intervals are displayed as a sorted set of intervals.
-    /// See [`Interval::fmt`](../interval/struct.Interval.html#method.fmt-1) for more detail on how intervals are formatted.
-    /// ```
-    /// # use interval::prelude::*;
-    /// assert_eq!(format!("{}", [(3, 5)].to_interval_set()), "[3..5]");
-    /// assert_eq!(format!("{}", [(4, 4), (8, 9)].to_interval_set()), "{[4..4][8..9]}");
-    /// assert_eq!(format!("{}", IntervalSet::<u32>::empty()), "{}");
-    /// ```
-    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
-        if self.intervals.len() == 1 {
-            self.intervals[0].fmt(formatter)
+    // An empty interval gives the empty set, so that no empty interval is stored even in release builds.
+    fn from_interval(i: Interval<Bound>) -> IntervalSet<Bound> {
+        if i.is_empty() {
+            return IntervalSet::empty();
+        }
+        let size = i.size().clone();
+        IntervalSet {
+            intervals: [i].to_vec(),
+            size,
+        }
+    }
+
+    fn front(&self) -> &Interval<Bound> {
+        debug_assert!(
+            !self.is_empty(),
+            "Cannot access the first interval of an empty set."
+        );
+        &self.intervals[0]
+    }
+
+    fn back_idx(&self) -> usize {
+        self.intervals.len() - 1
+    }
+
+    fn back(&self) -> &Interval<Bound> {
+        debug_assert!(
+            !self.is_empty(),
+            "Cannot access the last interval of an empty set."
+        );
+        &self.intervals[self.back_idx()]
+    }
+
+    fn span(&self) -> Interval<Bound> {
+        if self.is_empty() {
+            Interval::empty()
         } else {
-            formatter.write_str("{")?;
-            for interval in &self.intervals {
-                formatter.write_fmt(format_args!("{}", interval))?;
+            self.span_slice(0, self.back_idx())
+        }
+    }
+
+    fn span_slice(&self, left: usize, right: usize) -> Interval<Bound> {
+        Interval::new(self.intervals[left].lower(), self.intervals[right].upper())
+    }
+
+    fn push(&mut self, x: Interval<Bound>) {
+        debug_assert!(!x.is_empty(), "Cannot push empty interval.");
+        debug_assert!(self.is_empty() || !joinable(self.back(), &x),
+      "The intervals array must be ordered and intervals must not be joinable. For a safe push, use the union operation.");
+
+        self.size = self.size.clone() + x.size();
+        self.intervals.push(x);
+    }
+
+    // Removes all the intervals, keeping the allocated storage.
+    fn clear(&mut self) {
+        self.intervals.clear();
+        self.size = <Bound as Width>::Output::zero();
+    }
+
+    fn pop(&mut self) -> Option<Interval<Bound>> {
+        if let Some(x) = self.intervals.pop() {
+            self.size = self.size.clone() - x.size();
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    fn join_or_push(&mut self, x: Interval<Bound>) {
+        if self.is_empty() {
+            self.push(x);
+        } else {
+            debug_assert!(!x.is_empty(), "Cannot push empty interval.");
+            debug_assert!(self.back().lower() <= x.lower(),
+        "This operation is only for pushing interval to the back of the array, possibly overlapping with the last element.");
+
+            let joint = if joinable(self.back(), &x) {
+                self.pop().unwrap().hull(&x)
+            } else {
+                x
+            };
+            self.push(joint);
+        }
+    }
+
+    /// Expects the given iterable to be in-order - as we are iterating through
+    /// it, each [`Interval<Bound>`] should belong on the upper end of the
+    /// [`IntervalSet`]. Otherwise this method will panic.
+    fn extend_at_back<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = Interval<Bound>>,
+        Bound: PartialOrd,
+    {
+        for interval in iterable {
+            self.join_or_push(interval);
+        }
+    }
+
+    fn find_interval_between(
+        &self,
+        value: &Bound,
+        mut left: usize,
+        mut right: usize,
+    ) -> (usize, usize) {
+        debug_assert!(left <= right);
+        debug_assert!(right < self.intervals.len());
+        debug_assert!(self.span_slice(left, right).contains(value));
+
+        while left <= right {
+            let mid_idx = left + (right - left) / 2;
+            let mid = &self.intervals[mid_idx];
+            if &mid.lower() > value {
+                right = mid_idx - 1;
+            } else if &mid.upper() < value {
+                left = mid_idx + 1;
+            } else {
+                return (mid_idx, mid_idx);
             }
-            formatter.write_str("}")
+        }
+        (right, left)
+    }
+
+    // Returns the indexes of the left and right interval of `value`.
+    // If the value is outside `self`, returns None.
+    // If the value is inside one of the interval, the indexes will be equal.
+    fn find_interval(&self, value: &Bound) -> Option<(usize, usize)> {
+        if !self.span().contains(value) {
+            None
+        } else {
+            Some(self.find_interval_between(value, 0, self.back_idx()))
+        }
+    }
+
+    fn for_all_pairs<F>(&self, other: &IntervalSet<Bound>, f: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Interval<Bound>, &Interval<Bound>) -> Interval<Bound>,
+    {
+        let mut res = IntervalSet::empty();
+        for i in &self.intervals {
+            for j in &other.intervals {
+                res = res.union(&IntervalSet::from_interval(f(i, j)));
+            }
+        }
+        res
+    }
+
+    // Precondition: `f` must not change the size of the interval.
+    fn stable_map<F>(&self, f: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Interval<Bound>) -> Interval<Bound>,
+    {
+        IntervalSet {
+            intervals: self.intervals.iter().map(f).collect(),
+            size: self.size.clone(),
+        }
+    }
+
+    // Precondition: `f` must be monotonic, increasing unless `reversed` is set, and must not make intervals joinable.
+    fn map<F>(&self, reversed: bool, f: F) -> IntervalSet<Bound>
+    where
+        F: Fn(&Interval<Bound>) -> Interval<Bound>,
+    {
+        let push = |mut r: IntervalSet<Bound>, i| {
+            r.push(f(i));
+            r
+        };
+        if reversed {
+            self.intervals.iter().rev().fold(IntervalSet::empty(), push)
+        } else {
+            self.intervals.iter().fold(IntervalSet::empty(), push)
         }
     }
 }
 
-impl<Bound> Join for IntervalSet<Bound>
+// Divides `value` by a positive `den` with the rounding `mode` (`Outward` is not a rounding of a single value).
+fn div_round<Bound>(value: Bound, den: &Bound, mode: RoundMode) -> Bound
 where
-    Bound: Width + Num,
+    Bound: Integer + Clone,
 {
-    fn join(self, other: IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.intersection(&other)
+    match mode {
+        RoundMode::Floor => value.div_floor(den),
+        RoundMode::Ceil => Integer::div_ceil(&value, den),
+        RoundMode::Nearest => {
+            // `rem` is in `[0, den)`, the fractional part is at least a half when `rem >= den - rem`.
+            let rem = value.mod_floor(den);
+            let floor = value.div_floor(den);
+            if rem >= den.clone() - rem.clone() {
+                floor + Bound::one()
+            } else {
+                floor
+            }
+        }
+        RoundMode::Outward => unreachable!("`div_round` expects `Floor`, `Ceil` or `Nearest`."),
     }
 }
 
-impl<Bound> Meet for IntervalSet<Bound>
+// Calculates `(q, r)` such that `a * b == q * den + r` with `0 <= r < den`, without computing `a * b`.
+// Precondition: `0 <= a < den` and `0 <= b <= den`.
+fn mul_div_rem<Bound>(a: Bound, b: Bound, den: &Bound) -> (Bound, Bound)
 where
-    Bound: Width + Num,
+    Bound: Integer + Clone,
 {
-    fn meet(self, other: IntervalSet<Bound>) -> IntervalSet<Bound> {
-        self.union(&other)
+    // Adds two remainders, returning their sum modulo `den` and whether it wrapped around.
+    let add_rem = |x: Bound, y: Bound| {
+        if x >= den.clone() - y.clone() {
+            (x - (den.clone() - y), Bound::one())
+        } else {
+            (x + y, Bound::zero())
+        }
+    };
+    let two = Bound::one() + Bound::one();
+    let (mut q, mut r) = (Bound::zero(), Bound::zero());
+    // `a * 2^k == a_q * den + a_r`, where `2^k` is the weight of the current bit of `b`.
+    let (mut a_q, mut a_r) = (Bound::zero(), a);
+    let mut b = b;
+    while b > Bound::zero() {
+        if b.is_odd() {
+            let (sum, carry) = add_rem(r, a_r.clone());
+            r = sum;
+            q = q + a_q.clone() + carry;
+        }
+        b = b / two.clone();
+        if b > Bound::zero() {
+            let (double, carry) = add_rem(a_r.clone(), a_r);
+            a_r = double;
+            a_q = a_q.clone() + a_q + carry;
+        }
     }
+    (q, r)
 }
 
-impl<Bound> Entailment for IntervalSet<Bound>
+// Iterates over the values of `[lower, upper]` without stepping past `upper`.
+fn interval_values<Bound>(lower: Bound, upper: Bound) -> impl Iterator<Item = Bound>
 where
-    Bound: Width + Num,
+    Bound: Num + Ord + Clone,
 {
-    fn entail(&self, other: &IntervalSet<Bound>) -> SKleene {
-        if self.is_subset(other) {
-            SKleene::True
-        } else if other.is_subset(self) {
-            SKleene::False
+    let first = if lower <= upper { Some(lower) } else { None };
+    ::std::iter::successors(first, move |v| {
+        if *v < upper {
+            Some(v.clone() + Bound::one())
         } else {
-            SKleene::Unknown
+            None
         }
+    })
+}
+
+// Maximal number of characters rendered by `IntervalSet::ascii_bar`.
+const ASCII_BAR_MAX_WIDTH: usize = 4096;
+
+// Calculates the number of bits of the binary representation of a non-negative value.
+fn bit_length<T>(mut value: T) -> u32
+where
+    T: Num + Ord + Clone,
+{
+    let two = T::one() + T::one();
+    let mut bits = 0;
+    while value > T::zero() {
+        value = value / two.clone();
+        bits += 1;
     }
+    bits
 }
 
-impl<Bound> Top for IntervalSet<Bound>
+// Calculates `lower + width`, the result must be representable by `Bound`.
+fn add_width<Bound>(lower: Bound, width: <Bound as Width>::Output) -> Bound
+where
+    Bound: Width + Num + NumCast,
+    <Bound as Width>::Output: ToPrimitive,
+{
+    static NOT_REPRESENTABLE: &str =
+        "`add_width` expects `lower + width` to be representable by the bound.";
+    // A width might not fit in a signed bound (e.g. `u8` for `i8`), but its halves always do.
+    let two = <Bound as Width>::Output::one() + <Bound as Width>::Output::one();
+    let half = width.clone() / two;
+    let rest = width - half.clone();
+    let half = <Bound as NumCast>::from(half).expect(NOT_REPRESENTABLE);
+    let rest = <Bound as NumCast>::from(rest).expect(NOT_REPRESENTABLE);
+    lower + half + rest
+}
+
+// Checks whether at most `gap` values lie strictly between `upper` and `lower`, counted in `Width::Output`
+// so that far apart bounds do not overflow.
+// Precondition: `upper < lower`.
+fn gap_at_most<Bound>(upper: Bound, lower: Bound, gap: &Bound) -> bool
 where
     Bound: Width + Num,
 {
-    fn top() -> IntervalSet<Bound> {
-        IntervalSet::empty()
+    let upper = upper + Bound::one();
+    if upper == lower {
+        *gap >= Bound::zero()
+    } else {
+        // There are `gap` values in `[0, gap - 1]`.
+        *gap >= Bound::one()
+            && Bound::width(&upper, &(lower - Bound::one()))
+                <= Bound::width(&Bound::zero(), &(gap.clone() - Bound::one()))
     }
 }
 
-impl<Bound> Bot for IntervalSet<Bound>
+fn joinable<Bound>(first: &Interval<Bound>, second: &Interval<Bound>) -> bool
 where
     Bound: Width + Num,
 {
-    fn bot() -> IntervalSet<Bound> {
-        IntervalSet::whole()
+    if first.upper() == Bound::max_value() {
+        true
+    } else {
+        first.upper() + Bound::one() >= second.lower()
     }
 }
 
-#[allow(non_upper_case_globals)]
-#[cfg(test)]
-mod tests {
-    use serde_test::{assert_tokens, Token};
-
-    use super::*;
-
-    const extend_example: [(i32, i32); 2] = [(11, 33), (-55, -44)];
-
-    fn test_inside_outside(is: IntervalSet<i32>, inside: Vec<i32>, outside: Vec<i32>) {
-        for i in &inside {
-            assert!(
-                is.contains(i),
-                "{} is not contained inside {}, but it should.",
-                i,
-                is
-            );
-        }
-        for i in &outside {
-            assert!(
+impl<Bound> Extend<Interval<Bound>> for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Inserts additional intervals into an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut interval_set = IntervalSet::<u32>::empty();
+    /// assert_eq!(interval_set, Vec::new().to_interval_set());
+    /// interval_set.extend([Interval::new(2, 3), Interval::new(6, 7)]);
+    /// // Now the set contains two disjoint intervals.
+    /// assert_eq!(interval_set, [(2, 3), (6, 7)].to_interval_set());
+    /// // Unify the intervals with the missing items to create a single interval.
+    /// interval_set.extend([Interval::singleton(4), Interval::singleton(5)]);
+    /// assert_eq!(interval_set, [(2, 7)].to_interval_set());
+    /// ```
+    fn extend<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = Interval<Bound>>,
+    {
+        let mut intervals: Vec<_> = iterable.into_iter().map(|i| i.to_interval()).collect();
+        intervals.sort_unstable_by_key(|i| i.lower());
+        // Fast path: the new intervals all belong at the back of the set.
+        let at_back = match intervals.first() {
+            Some(first) => self.is_empty() || self.back().lower() <= first.lower(),
+            None => true,
+        };
+        if at_back {
+            self.extend_at_back(intervals);
+        } else {
+            let mut set = IntervalSet::empty();
+            set.extend_at_back(intervals);
+            *self = self.union(&set);
+        }
+    }
+}
+
+impl<Bound: Width + Num> Eq for IntervalSet<Bound> {}
+
+impl<Bound> PartialEq<IntervalSet<Bound>> for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    // Checks whether two interval sets are the same.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let single_interval = [(1, 5)].to_interval_set();
+    /// let equivalent_interval = [(1, 2), (2, 5)].to_interval_set();
+    /// assert_eq!(single_interval, equivalent_interval);
+    /// ```
+    /// Empty intervals are the same as each other, but not non-empty intervals.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::<usize>::empty(), IntervalSet::<usize>::empty());
+    /// assert_ne!(IntervalSet::empty(), [(2, 3), (8, 9)].to_interval_set());
+    /// ```
+    fn eq(&self, other: &IntervalSet<Bound>) -> bool {
+        if self.size() != other.size() {
+            false
+        } else {
+            self.intervals == other.intervals
+        }
+    }
+}
+
+impl<Bound> PartialEq<Interval<Bound>> for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Checks whether an interval set is made of exactly one interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(1, 4), (5, 9)].to_interval_set(), Interval::new(1, 9));
+    /// assert_ne!([(1, 4), (6, 9)].to_interval_set(), Interval::new(1, 9));
+    /// assert_ne!([(1, 4)].to_interval_set(), Interval::new(1, 9));
+    /// ```
+    /// The empty interval set is only equal to the empty interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::<usize>::empty(), Interval::empty());
+    /// assert_ne!(IntervalSet::empty(), Interval::new(2, 3));
+    /// assert_ne!(IntervalSet::new(2, 3), Interval::empty());
+    /// ```
+    fn eq(&self, other: &Interval<Bound>) -> bool {
+        if other.is_empty() {
+            self.is_empty()
+        } else {
+            self.intervals.len() == 1 && &self.intervals[0] == other
+        }
+    }
+}
+
+impl<Bound> PartialEq<IntervalSet<Bound>> for Interval<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Checks whether an interval set is made of exactly this interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(Interval::new(1, 9), [(1, 4), (5, 9)].to_interval_set());
+    /// assert_ne!(Interval::new(1, 9), [(1, 4), (6, 9)].to_interval_set());
+    /// assert_eq!(Interval::<usize>::empty(), IntervalSet::empty());
+    /// ```
+    fn eq(&self, other: &IntervalSet<Bound>) -> bool {
+        other == self
+    }
+}
+
+impl<Bound> Range for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Constructs an interval set from a specified interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval = IntervalSet::new(2, 4);
+    /// assert!(interval.contains(&2));
+    /// assert!(interval.contains(&3));
+    /// assert!(interval.contains(&4));
+    ///
+    /// assert!(!interval.contains(&1));
+    /// assert!(!interval.contains(&5));
+    /// ```
+    /// Constructing an empty interval set is done via the [IntervalSet::empty()] method.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let empty_interval = IntervalSet::<u16>::empty();
+    /// ```
+    fn new(lb: Bound, ub: Bound) -> IntervalSet<Bound> {
+        debug_assert!(lb <= ub, "Cannot build empty interval set with an invalid range. use crate::IntervalSet::empty().");
+        let i = Interval::new(lb, ub);
+        IntervalSet::from_interval(i)
+    }
+}
+
+impl<Bound> Whole for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Constructs an interval set using the entire range an interval can represent.
+    /// The bounds of the interval set are bounded in the same way as [`Interval::whole`].
+    /// This is different for unsigned and signed integers:
+    /// ```
+    /// # use interval::prelude::*;
+    /// let unsigned_interval = IntervalSet::<u8>::whole();
+    /// assert_eq!(unsigned_interval, IntervalSet::new(0, 254));
+    ///
+    /// let signed_interval = IntervalSet::<i8>::whole();
+    /// assert_eq!(signed_interval, IntervalSet::new(-127, 127));
+    /// ```
+    fn whole() -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        res.push(Interval::whole());
+        res
+    }
+}
+
+impl<Bound> Bounded for IntervalSet<Bound>
+where
+    Bound: Width + Num + PartialOrd,
+{
+    /// Returns the smallest value contained in an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(8, 20)].to_interval_set().lower(), 8);
+    /// assert_eq!([(-5, 11), (10, 30)].to_interval_set().lower(), -5);
+    /// assert_eq!(IntervalSet::singleton(7).lower(), 7);
+    /// ```
+    /// However, this will panic on an empty interval.
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// IntervalSet::<u8>::empty().lower(); // panics!
+    /// ```
+    fn lower(&self) -> Bound {
+        debug_assert!(
+            !self.is_empty(),
+            "Cannot access lower bound on empty interval."
+        );
+        self.front().lower()
+    }
+
+    /// Returns the largest value contained in an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(8, 20)].to_interval_set().upper(), 20);
+    /// assert_eq!([(-5, 11), (10, 30)].to_interval_set().upper(), 30);
+    /// assert_eq!(IntervalSet::singleton(7).upper(), 7);
+    /// ```
+    /// However, this will panic on an empty interval.
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// IntervalSet::<u8>::empty().upper(); // panics!
+    /// ```
+    fn upper(&self) -> Bound {
+        debug_assert!(
+            !self.is_empty(),
+            "Cannot access upper bound on empty interval."
+        );
+        self.back().upper()
+    }
+}
+
+impl<Bound: Width + Num> Singleton for IntervalSet<Bound> {
+    /// Constructs an interval set containing a single value.
+    /// This set contains a single interval with the same upper and lower bounds.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set_5 = IntervalSet::singleton(5);
+    /// assert_eq!(interval_set_5.size(), 1 as u32);
+    /// assert_eq!(interval_set_5.lower(), interval_set_5.upper());
+    /// assert!(interval_set_5.contains(&5));
+    /// assert!(!interval_set_5.is_empty());
+    /// ```
+    fn singleton(x: Bound) -> IntervalSet<Bound> {
+        IntervalSet::new(x.clone(), x)
+    }
+}
+
+impl<Bound: Width + Num> Empty for IntervalSet<Bound> {
+    /// Constructs an empty interval set.
+    /// The type needs to be specified or inferred as `Interval` is parametrized by its input.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::<i32>::empty().size(), 0);
+    /// assert_eq!(IntervalSet::<u32>::empty().size(), 0);
+    /// assert!(IntervalSet::<u16>::empty().is_empty());
+    /// ```
+    fn empty() -> IntervalSet<Bound> {
+        IntervalSet {
+            intervals: Vec::new(),
+            size: <<Bound as Width>::Output>::zero(),
+        }
+    }
+}
+
+/// `IsSingleton` and `IsEmpty` are defined automatically in `gcollections`.
+impl<Bound: Width + Num> Cardinality for IntervalSet<Bound> {
+    type Size = <Bound as Width>::Output;
+
+    /// Calculates the size of an interval set (the number of integers it contains).
+    /// This includes the endpoints of all intervals.
+    /// The size of the interval set is unsigned, but has the same number of bits as the interval bounds.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::<i32>::singleton(8).size(), 1 as u32);
+    /// assert_eq!(IntervalSet::<usize>::new(1, 10).size(), 10 as usize);
+    /// // Default is to use i32.
+    /// assert_eq!([(3, 5), (8, 9)].to_interval_set().size(), (3 + 2) as u32);
+    /// assert_eq!(IntervalSet::<i64>::empty().size(), 0);
+    /// // Doesn't overflow:
+    /// assert_eq!(IntervalSet::<usize>::whole().size(), usize::max_value());
+    /// ```
+    /// The size is always exact, it never saturates: [`Width`] excludes one extreme value of the bound type
+    /// so that even the size of the whole set is representable, and a set missing a single value has a smaller size.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let whole = IntervalSet::<u8>::whole();
+    /// assert_eq!(whole.size(), 255);
+    /// assert_eq!(whole.difference(&0).size(), 254);
+    /// ```
+    fn size(&self) -> <Bound as Width>::Output {
+        self.size.clone()
+    }
+}
+
+impl<Bound: Width> Contains for IntervalSet<Bound> {
+    /// Calculates whether an interval contains a value.
+    /// Only comparisons are needed, so this is available for bounds without arithmetic.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
+    /// assert!(interval_set.contains(&3));
+    /// assert!(interval_set.contains(&4));
+    /// assert!(interval_set.contains(&5));
+    /// assert!(interval_set.contains(&8));
+    /// assert!(interval_set.contains(&9));
+    ///
+    /// assert!(!interval_set.contains(&1));
+    /// assert!(!interval_set.contains(&2));
+    /// assert!(!interval_set.contains(&6));
+    /// assert!(!interval_set.contains(&7));
+    /// assert!(!interval_set.contains(&10));
+    /// ```
+    fn contains(&self, value: &Bound) -> bool {
+        let idx = self.intervals.partition_point(|i| i.upper_ref() < value);
+        idx < self.intervals.len() && self.intervals[idx].lower_ref() <= value
+    }
+}
+
+fn advance_one<I, F, Item>(a: &mut Peekable<I>, b: &mut Peekable<I>, choose: F) -> Item
+where
+    I: Iterator<Item = Item>,
+    F: Fn(&Item, &Item) -> bool,
+    Item: Bounded,
+{
+    static NON_EMPTY_PRECONDITION: &str =
+        "`advance_one` expects both interval iterators to be non_empty.";
+    let who_advance = {
+        let i = a.peek().expect(NON_EMPTY_PRECONDITION);
+        let j = b.peek().expect(NON_EMPTY_PRECONDITION);
+        choose(i, j)
+    };
+    let to_advance = if who_advance { a } else { b };
+    to_advance.next().unwrap()
+}
+
+fn advance_lower<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> Item
+where
+    I: Iterator<Item = Item>,
+    Item: Bounded + Collection<Item = B>,
+    B: Ord,
+{
+    advance_one(a, b, |i, j| i.lower() < j.lower())
+}
+
+// Advance the one with the lower upper bound.
+fn advance_lub<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> Item
+where
+    I: Iterator<Item = Item>,
+    Item: Bounded + Collection<Item = B>,
+    B: Ord,
+{
+    advance_one(a, b, |i, j| i.upper() < j.upper())
+}
+
+impl<Bound> Union<Bound> for IntervalSet<Bound>
+where
+    Bound: Width + Num + Clone,
+{
+    type Output = IntervalSet<Bound>;
+
+    /// Adds a value to the interval set.
+    /// The value does not have to below to an existing interval,
+    /// but if it does, those intervals will be merged.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 4), (6, 7)].to_interval_set();
+    /// assert_eq!(interval_set.union(&10), [(1, 4), (6, 7), (10, 10)].to_interval_set());
+    ///
+    /// assert_eq!(interval_set.union(&3), interval_set);
+    /// assert_eq!(interval_set.union(&6), interval_set);
+    ///
+    /// assert_eq!(interval_set.union(&0), [(0, 4), (6, 7)].to_interval_set());
+    /// assert_eq!(interval_set.union(&8), [(1, 4), (6, 8)].to_interval_set());
+    ///
+    /// assert_eq!(interval_set.union(&5), [(1, 7)].to_interval_set());
+    /// ```
+    fn union(&self, rhs: &Bound) -> IntervalSet<Bound> {
+        self.union(&IntervalSet::singleton(rhs.clone()))
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    // Counts the calls to `IntervalSet::union` to test the fast path of `extend`.
+    static UNION_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+impl<Bound: Width + Num> Union for IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the union of two interval sets.
+    /// This returns an interval set containing all the values in the two that were unified.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.union(&b), [(1, 5), (7, 8), (10, 15)].to_interval_set());
+    /// ```
+    fn union(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        #[cfg(test)]
+        UNION_CALLS.with(|calls| calls.set(calls.get() + 1));
+        let mut res = IntervalSet::empty();
+        self.union_into(rhs, &mut res);
+        res
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Calculates the union of two interval sets known to be disjoint, by merging their sorted intervals.
+    /// Adjacent intervals, such as `(1, 3)` and `(4, 5)`, are still fused.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (10, 11)].to_interval_set();
+    /// let b = [(4, 5), (7, 8)].to_interval_set();
+    /// assert_eq!(a.disjoint_union(&b), [(1, 5), (7, 8), (10, 11)].to_interval_set());
+    /// ```
+    /// The sets must not overlap, this precondition is checked when debug assertions are enabled.
+    /// Otherwise, the result is the same as [`Union::union`].
+    /// ```should_panic
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3)].to_interval_set();
+    /// let _ = a.disjoint_union(&[(3, 5)].to_interval_set()); // panics!
+    /// ```
+    pub fn disjoint_union(&self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        debug_assert!(
+            !self.overlap(other),
+            "`disjoint_union` expects disjoint interval sets."
+        );
+        let mut res = IntervalSet::empty();
+        res.intervals
+            .reserve_exact(self.intervals.len() + other.intervals.len());
+        let mut a = self.intervals.iter().peekable();
+        let mut b = other.intervals.iter().peekable();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let next = if x.lower() <= y.lower() {
+                a.next()
+            } else {
+                b.next()
+            };
+            res.join_or_push(next.unwrap().clone());
+        }
+        res.extend_at_back(a.chain(b).cloned());
+        res
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    // Counts the steps of `advance_to_first_overlapping` to test the shortcuts of `overlap`.
+    static OVERLAP_SWEEP_STEPS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+// Returns `false` when one of the iterator is consumed.
+// Iterators are not consumed if the intervals are already overlapping.
+fn advance_to_first_overlapping<I, Item, B>(a: &mut Peekable<I>, b: &mut Peekable<I>) -> bool
+where
+    I: Iterator<Item = Item>,
+    Item: Bounded + Overlap + Collection<Item = B>,
+    B: Ord,
+{
+    while a.peek().is_some() && b.peek().is_some() {
+        #[cfg(test)]
+        OVERLAP_SWEEP_STEPS.with(|steps| steps.set(steps.get() + 1));
+        let overlapping = {
+            let i = a.peek().unwrap();
+            let j = b.peek().unwrap();
+            i.overlap(j)
+        };
+        if overlapping {
+            return true;
+        } else {
+            advance_lower(a, b);
+        }
+    }
+    false
+}
+
+impl<Bound> Intersection<Bound> for IntervalSet<Bound>
+where
+    Bound: Width + Num + Clone,
+{
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates whether a value is contained in an interval.
+    /// Returns the value as an interval set if it is in the interval
+    /// and an empty interval set if not.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 4), (6, 7)].to_interval_set();
+    /// assert_eq!(interval_set.intersection(&10), IntervalSet::empty());
+    ///
+    /// assert_eq!(interval_set.intersection(&3), IntervalSet::singleton(3));
+    /// assert_eq!(interval_set.intersection(&6), IntervalSet::singleton(6));
+    /// ```
+    fn intersection(&self, rhs: &Bound) -> IntervalSet<Bound> {
+        self.intersection(&IntervalSet::singleton(rhs.clone()))
+    }
+}
+
+impl<Bound> Intersection<(Bound, Bound)> for IntervalSet<Bound>
+where
+    Bound: Width + Num + Clone,
+{
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the intersection of an interval set and the interval `[lb, ub]` given as a tuple `(lb, ub)`.
+    /// This is the same as [`IntervalSet::intersect_interval`], and gives the empty set when `lb > ub`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 4), (6, 7), (10, 12)].to_interval_set();
+    /// assert_eq!(interval_set.intersection(&(3, 8)), [(3, 4), (6, 7)].to_interval_set());
+    /// assert_eq!(interval_set.intersection(&(5, 5)), IntervalSet::empty());
+    /// assert_eq!(interval_set.intersection(&(8, 3)), IntervalSet::empty());
+    /// ```
+    fn intersection(&self, rhs: &(Bound, Bound)) -> IntervalSet<Bound> {
+        self.intersect_interval(&Interval::new(rhs.0.clone(), rhs.1.clone()))
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Calculates the intersection of an interval set and an interval.
+    /// This is the same as the intersection with the interval set made of the interval, but it does not iterate over the intervals outside of `i`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (6, 8), (10, 11)].to_interval_set();
+    /// assert_eq!(interval_set.intersect_interval(&Interval::new(2, 7)), [(2, 3), (6, 7)].to_interval_set());
+    /// assert_eq!(interval_set.intersect_interval(&Interval::new(4, 5)), IntervalSet::empty());
+    /// assert_eq!(interval_set.intersect_interval(&Interval::empty()), IntervalSet::empty());
+    /// ```
+    pub fn intersect_interval(&self, i: &Interval<Bound>) -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        if i.is_empty() {
+            return res;
+        }
+        let start = self.intervals.partition_point(|x| x.upper() < i.lower());
+        for x in &self.intervals[start..] {
+            if x.lower() > i.upper() {
+                break;
+            }
+            res.push(x.intersection(i));
+        }
+        res
+    }
+
+    /// Calculates the coverage map of two interval sets: the maximal intervals of their union, in increasing order,
+    /// each annotated with the number of sets covering it (1 or 2).
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(0, 5)].to_interval_set();
+    /// let b = [(3, 8)].to_interval_set();
+    /// assert_eq!(a.coverage_union(&b), vec![(Interval::new(0, 2), 1), (Interval::new(3, 5), 2), (Interval::new(6, 8), 1)]);
+    /// ```
+    pub fn coverage_union(&self, other: &IntervalSet<Bound>) -> Vec<(Interval<Bound>, u8)> {
+        // Each run starts at the lower bound of an interval or right after the upper bound of one.
+        let mut res: Vec<(Interval<Bound>, u8)> =
+            Vec::with_capacity(2 * (self.intervals.len() + other.intervals.len()));
+        // Adjacent runs covered by the same number of sets are merged.
+        let mut push = |x: Interval<Bound>, count: u8| match res.last_mut() {
+            Some((last, last_count)) if *last_count == count && joinable(last, &x) => {
+                *last = last.hull(&x);
+            }
+            _ => res.push((x, count)),
+        };
+        let a = &mut self.intervals.iter().cloned();
+        let b = &mut other.intervals.iter().cloned();
+        // The parts of the current intervals of each set that are not covered by the result yet.
+        let (mut x, mut y) = (a.next(), b.next());
+        loop {
+            match (x.take(), y.take()) {
+                (None, None) => break,
+                (Some(i), None) => {
+                    push(i, 1);
+                    x = a.next();
+                }
+                (None, Some(j)) => {
+                    push(j, 1);
+                    y = b.next();
+                }
+                (Some(i), Some(j)) => {
+                    if i.upper() < j.lower() {
+                        push(i, 1);
+                        x = a.next();
+                        y = Some(j);
+                    } else if j.upper() < i.lower() {
+                        push(j, 1);
+                        x = Some(i);
+                        y = b.next();
+                    } else {
+                        // The values below the common part are only in one set.
+                        if i.lower() < j.lower() {
+                            push(Interval::new(i.lower(), j.lower() - Bound::one()), 1);
+                        } else if j.lower() < i.lower() {
+                            push(Interval::new(j.lower(), i.lower() - Bound::one()), 1);
+                        }
+                        push(i.intersection(&j), 2);
+                        // The common part ends at the lowest upper bound, the rest of the other interval is kept.
+                        x = if i.upper() > j.upper() {
+                            Some(Interval::new(j.upper() + Bound::one(), i.upper()))
+                        } else {
+                            a.next()
+                        };
+                        y = if j.upper() > i.upper() {
+                            Some(Interval::new(i.upper() + Bound::one(), j.upper()))
+                        } else {
+                            b.next()
+                        };
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Counts the values shared by both interval sets, without building their intersection.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.intersection_size(&b), a.intersection(&b).size());
+    /// ```
+    pub fn intersection_size(&self, other: &IntervalSet<Bound>) -> <Bound as Width>::Output {
+        let mut size = <Bound as Width>::Output::zero();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let a = &self.intervals[i];
+            let b = &other.intervals[j];
+            let lower = max(a.lower(), b.lower());
+            let upper = min(a.upper(), b.upper());
+            if lower <= upper {
+                size = size + Bound::width(&lower, &upper);
+            }
+            if a.upper() < b.upper() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        size
+    }
+
+    /// Counts the values in either interval set, without building their union.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.union_size(&b), a.union(&b).size());
+    /// ```
+    pub fn union_size(&self, other: &IntervalSet<Bound>) -> <Bound as Width>::Output {
+        self.size() + (other.size() - self.intersection_size(other))
+    }
+
+    /// Counts the values in exactly one of the interval sets, without building their symmetric difference.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.symmetric_difference_size(&b), a.symmetric_difference(&b).size());
+    /// ```
+    pub fn symmetric_difference_size(
+        &self,
+        other: &IntervalSet<Bound>,
+    ) -> <Bound as Width>::Output {
+        let shared = self.intersection_size(other);
+        (self.size() - shared.clone()) + (other.size() - shared)
+    }
+}
+
+impl<Bound: Width + Num> Intersection for IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the intersection of two interval sets.
+    /// This returns an interval set containing all the values in the both.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.intersection(&b), [(2, 3), (8, 8)].to_interval_set());
+    /// ```
+    fn intersection(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        self.intersection_into(rhs, &mut res);
+        res
+    }
+}
+
+// Pushes the values in `[lower + 1, upper - 1]`, if any. The bounds next to `lower` and `upper` saturate:
+// when one of them is not representable, there is no value on this side and nothing is pushed.
+fn push_gap<Bound>(lower: &Bound, upper: &Bound, res: &mut IntervalSet<Bound>)
+where
+    Bound: Width + Num + CheckedAdd + CheckedSub,
+{
+    let first = lower.checked_add(&Bound::one());
+    let last = upper.checked_sub(&Bound::one());
+    if let (Some(first), Some(last)) = (first, last) {
+        if first <= last {
+            res.push(Interval::new(first, last));
+        }
+    }
+}
+
+fn push_left_complement<Bound>(x: &Interval<Bound>, res: &mut IntervalSet<Bound>)
+where
+    Bound: Width + Num + CheckedAdd + CheckedSub,
+{
+    let min = <Bound as Width>::min_value();
+    if let Some(last) = x.lower().checked_sub(&Bound::one()) {
+        if min <= last {
+            res.push(Interval::new(min, last));
+        }
+    }
+}
+
+fn push_right_complement<Bound>(x: &Interval<Bound>, res: &mut IntervalSet<Bound>)
+where
+    Bound: Width + Num + CheckedAdd + CheckedSub,
+{
+    let max = <Bound as Width>::max_value();
+    if let Some(first) = x.upper().checked_add(&Bound::one()) {
+        if first <= max {
+            res.push(Interval::new(first, max));
+        }
+    }
+}
+
+impl<Bound> Complement for IntervalSet<Bound>
+where
+    Bound: Width + Num + CheckedAdd + CheckedSub,
+{
+    /// Calculates all values that are excluded from the interval set.
+    /// Positive and negative infinity are represented with `Interval::whole().lower()` and `Interval::whole().upper()`;
+    /// these are [`Width::min_value`] and [`Width::max_value`], which exclude one extreme of the type (for example `255` for `u8`)
+    /// so that the size of any set fits in `Width::Output`.
+    /// No interval set can contain the excluded extreme, hence the complement is exact over every value a set can hold
+    /// and `x.complement().complement() == x` always holds.
+    /// The bounds next to the intervals are computed with checked arithmetic and saturate:
+    /// a bound that is not representable means that there is no value to add on this side.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(IntervalSet::<i64>::empty().complement(), IntervalSet::whole());
+    /// assert_eq!(IntervalSet::singleton(254u8).complement(), IntervalSet::new(0, 253));
+    ///
+    /// let neg_inf = IntervalSet::<i32>::whole().lower();
+    /// let pos_inf = IntervalSet::<i32>::whole().upper();
+    /// assert_eq!(IntervalSet::singleton(5).complement(), [(neg_inf, 4), (6, pos_inf)].to_interval_set());
+    /// assert_eq!([(2, 5), (8, 10)].to_interval_set().complement(), [(neg_inf, 1), (6, 7), (11, pos_inf)].to_interval_set());
+    /// ```
+    fn complement(&self) -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        if self.is_empty() {
+            res.push(Interval::whole());
+        } else {
+            push_left_complement(self.front(), &mut res);
+            for pair in self.intervals.windows(2) {
+                push_gap(&pair[0].upper(), &pair[1].lower(), &mut res);
+            }
+            push_right_complement(self.back(), &mut res);
+        }
+        res
+    }
+}
+
+impl<Bound> Difference<Bound> for IntervalSet<Bound>
+where
+    Bound: Width + Num + Clone,
+{
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the interval set after removing a single value.
+    /// This returns the original interval set if the value is not in the interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (5, 9)].to_interval_set();
+    /// assert_eq!(interval_set.difference(&4), interval_set);
+    /// assert_eq!(interval_set.difference(&5), [(1, 3), (6, 9)].to_interval_set());
+    /// assert_eq!(interval_set.difference(&7), [(1, 3), (5, 6), (8, 9)].to_interval_set());
+    /// ```
+    fn difference(&self, rhs: &Bound) -> IntervalSet<Bound> {
+        self.difference(&IntervalSet::singleton(rhs.clone()))
+    }
+}
+
+impl<Bound: Width + Num> Difference for IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the interval set containing all items in the left set,
+    /// but not in the right set.
+    /// The difference is not symmetric.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// assert_eq!(a.difference(&b), [(1, 1), (10, 11)].to_interval_set());
+    /// assert_eq!(b.difference(&a), [(4, 5), (7, 7), (12, 15)].to_interval_set());
+    /// ```
+    fn difference(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.difference_intervals(&rhs.intervals)
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Calculates the values of `universe` that are not in the interval set, i.e. the complement of the set relative to `universe`.
+    /// This is the same as `universe.difference(self)`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let universe = [(0, 20)].to_interval_set();
+    /// let interval_set = [(5, 6), (10, 12)].to_interval_set();
+    /// assert_eq!(interval_set.invert_within(&universe), [(0, 4), (7, 9), (13, 20)].to_interval_set());
+    /// ```
+    pub fn invert_within(&self, universe: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        universe.difference(self)
+    }
+
+    /// Calculates the set difference: the values of the interval set that are not in `other`.
+    /// This is an alias of [`Difference::difference`].
+    ///
+    /// Note that `a - b` is **not** the set difference: it is the arithmetic difference,
+    /// containing `x - y` for every `x` in `a` and `y` in `b`.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(0, 10)].to_interval_set();
+    /// let b = [(2, 3)].to_interval_set();
+    /// assert_eq!(a.set_minus(&b), [(0, 1), (4, 10)].to_interval_set());
+    /// assert_eq!(&a - &b, [(-3, 8)].to_interval_set());
+    /// ```
+    pub fn set_minus(&self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.difference(other)
+    }
+
+    /// Removes all the values of `holes` from the interval set in a single sweep.
+    /// The holes must be non-empty and sorted by lower bound, but they can overlap.
+    /// This is the same as the difference with each hole in turn.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(0, 100)].to_interval_set();
+    /// let holes = [Interval::new(10, 19), Interval::new(15, 30), Interval::new(50, 50)];
+    /// assert_eq!(interval_set.difference_intervals(&holes), [(0, 9), (31, 49), (51, 100)].to_interval_set());
+    /// ```
+    pub fn difference_intervals(&self, holes: &[Interval<Bound>]) -> IntervalSet<Bound> {
+        let mut res = IntervalSet::empty();
+        self.difference_intervals_into(holes, &mut res);
+        res
+    }
+
+    fn difference_intervals_into(&self, holes: &[Interval<Bound>], res: &mut IntervalSet<Bound>) {
+        debug_assert!(
+            holes.iter().all(|h| !h.is_empty()),
+            "`difference_intervals` expects non-empty holes."
+        );
+        debug_assert!(
+            holes
+                .windows(2)
+                .all(|pair| pair[0].lower() <= pair[1].lower()),
+            "`difference_intervals` expects holes sorted by lower bound."
+        );
+        res.clear();
+        let len = self.intervals.len();
+        difference_sweep(
+            &mut res.intervals,
+            len,
+            |_, idx| self.intervals[idx].clone(),
+            holes,
+        );
+        res.size = total_size(&res.intervals);
+    }
+}
+
+fn total_size<Bound: Width + Num>(intervals: &[Interval<Bound>]) -> <Bound as Width>::Output {
+    intervals
+        .iter()
+        .fold(<Bound as Width>::Output::zero(), |size, i| size + i.size())
+}
+
+// The sweeps below append their result to `buf` and read the `len` intervals of their input with `input(buf, idx)`,
+// so that the `_update` methods can keep their intervals at the front of `buf` and drop them once the sweep is done.
+
+fn difference_sweep<Bound, F>(
+    buf: &mut Vec<Interval<Bound>>,
+    len: usize,
+    input: F,
+    holes: &[Interval<Bound>],
+) where
+    Bound: Width + Num,
+    F: Fn(&[Interval<Bound>], usize) -> Interval<Bound>,
+{
+    // Index of the first hole that can still overlap the current interval of the input.
+    let mut j = 0;
+    for idx in 0..len {
+        let i = input(buf, idx);
+        while j < holes.len() && holes[j].upper() < i.lower() {
+            j += 1;
+        }
+        // `lower` is the smallest value of `i` not yet handled.
+        let mut lower = i.lower();
+        let mut covered = false;
+        while j < holes.len() && holes[j].lower() <= i.upper() {
+            let r = &holes[j];
+            if r.lower() > lower {
+                buf.push(Interval::new(lower.clone(), r.lower() - Bound::one()));
+            }
+            if r.upper() >= i.upper() {
+                // `r` might also overlap the next interval of the input.
+                covered = true;
+                break;
+            }
+            // A hole nested in a previous one does not move `lower` back.
+            lower = max(lower, r.upper() + Bound::one());
+            j += 1;
+        }
+        if !covered {
+            buf.push(Interval::new(lower, i.upper()));
+        }
+    }
+}
+
+fn intersection_sweep<Bound, F>(
+    buf: &mut Vec<Interval<Bound>>,
+    len: usize,
+    input: F,
+    other: &[Interval<Bound>],
+) where
+    Bound: Width + Num,
+    F: Fn(&[Interval<Bound>], usize) -> Interval<Bound>,
+{
+    let (mut i, mut j) = (0, 0);
+    while i < len && j < other.len() {
+        let x = input(buf, i);
+        let y = &other[j];
+        let z = x.intersection(y);
+        if !z.is_empty() {
+            buf.push(z);
+        }
+        // Advance the one with the lowest upper bound.
+        if x.upper() < y.upper() {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+}
+
+fn symmetric_difference_sweep<Bound, F>(
+    buf: &mut Vec<Interval<Bound>>,
+    len: usize,
+    input: F,
+    other: &[Interval<Bound>],
+) where
+    Bound: Width + Num,
+    F: Fn(&[Interval<Bound>], usize) -> Interval<Bound>,
+{
+    let start = buf.len();
+    // Pieces of both sides are pushed in order, but a piece can be adjacent to the previous one.
+    let push = |buf: &mut Vec<Interval<Bound>>, x: Interval<Bound>| {
+        if buf.len() > start && joinable(buf.last().unwrap(), &x) {
+            let last = buf.pop().unwrap();
+            buf.push(last.hull(&x));
+        } else {
+            buf.push(x);
+        }
+    };
+    let (mut i, mut j) = (0, 0);
+    // The parts of the current intervals of each side that are not handled yet.
+    let (mut a, mut b) = (None, None);
+    loop {
+        if a.is_none() && i < len {
+            a = Some(input(buf, i));
+            i += 1;
+        }
+        if b.is_none() && j < other.len() {
+            b = Some(other[j].clone());
+            j += 1;
+        }
+        match (a.take(), b.take()) {
+            (None, None) => break,
+            (Some(x), None) | (None, Some(x)) => push(buf, x),
+            (Some(x), Some(y)) => {
+                if x.upper() < y.lower() {
+                    push(buf, x);
+                    b = Some(y);
+                } else if y.upper() < x.lower() {
+                    push(buf, y);
+                    a = Some(x);
+                } else {
+                    // The values below the common part are only on one side.
+                    if x.lower() < y.lower() {
+                        push(buf, Interval::new(x.lower(), y.lower() - Bound::one()));
+                    } else if y.lower() < x.lower() {
+                        push(buf, Interval::new(y.lower(), x.lower() - Bound::one()));
+                    }
+                    // The common part ends at the lowest upper bound, the rest of the other interval is kept.
+                    if x.upper() < y.upper() {
+                        b = Some(Interval::new(x.upper() + Bound::one(), y.upper()));
+                    } else if y.upper() < x.upper() {
+                        a = Some(Interval::new(y.upper() + Bound::one(), x.upper()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<Bound> SymmetricDifference<Bound> for IntervalSet<Bound>
+where
+    Bound: Width + Num + Clone,
+{
+    type Output = IntervalSet<Bound>;
+
+    /// If the value is in the interval set, this return the interval set without the value.
+    /// If the value is not in the interval set, this returns the interval set extended with the value.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (5, 9)].to_interval_set();
+    /// assert_eq!(interval_set.symmetric_difference(&4), [(1, 9)].to_interval_set());
+    /// assert_eq!(interval_set.symmetric_difference(&4), interval_set.union(&4));
+
+    /// assert_eq!(interval_set.symmetric_difference(&5), [(1, 3), (6, 9)].to_interval_set());
+    /// assert_eq!(interval_set.symmetric_difference(&5), interval_set.difference(&5));
+    /// ```
+    fn symmetric_difference(&self, rhs: &Bound) -> IntervalSet<Bound> {
+        self.symmetric_difference(&IntervalSet::singleton(rhs.clone()))
+    }
+}
+
+impl<Bound: Width + Num> SymmetricDifference for IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the interval set containing that are in either the left set or the right set,
+    /// but not both.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (8, 8), (10, 11)].to_interval_set();
+    /// let b = [(2, 5), (7, 8), (12, 15)].to_interval_set();
+    /// let symmetric_difference = [(1, 1), (4, 5), (7, 7), (10, 15)].to_interval_set();
+    /// assert_eq!(a.symmetric_difference(&b), symmetric_difference);
+    /// assert_eq!(b.symmetric_difference(&a), symmetric_difference);
+    ///
+    /// assert_eq!(IntervalSet::union(&a.difference(&b), &b.difference(&a)), symmetric_difference);
+    /// ```
+    fn symmetric_difference(&self, rhs: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        let union = self.union(rhs);
+        let intersection = self.intersection(rhs);
+        union.difference(&intersection)
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Removes from the interval set the values of `other`.
+    /// This is the same as `*self = self.difference(other)` but reuses the storage of the set, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut interval_set = [(1, 9)].to_interval_set();
+    /// interval_set.difference_update(&[(3, 4)].to_interval_set());
+    /// assert_eq!(interval_set, [(1, 2), (5, 9)].to_interval_set());
+    /// ```
+    pub fn difference_update(&mut self, other: &IntervalSet<Bound>) {
+        let len = self.intervals.len();
+        difference_sweep(
+            &mut self.intervals,
+            len,
+            |buf, idx| buf[idx].clone(),
+            &other.intervals,
+        );
+        self.intervals.drain(..len);
+        self.size = total_size(&self.intervals);
+    }
+
+    /// Keeps only the values of the interval set that are also in `other`.
+    /// This is the same as `*self = self.intersection(other)` but reuses the storage of the set, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut interval_set = [(1, 9)].to_interval_set();
+    /// interval_set.intersection_update(&[(3, 4), (8, 12)].to_interval_set());
+    /// assert_eq!(interval_set, [(3, 4), (8, 9)].to_interval_set());
+    /// ```
+    pub fn intersection_update(&mut self, other: &IntervalSet<Bound>) {
+        let len = self.intervals.len();
+        intersection_sweep(
+            &mut self.intervals,
+            len,
+            |buf, idx| buf[idx].clone(),
+            &other.intervals,
+        );
+        self.intervals.drain(..len);
+        self.size = total_size(&self.intervals);
+    }
+
+    /// Keeps the values that are in either the interval set or `other` but not both.
+    /// This is the same as `*self = self.symmetric_difference(other)` but reuses the storage of the set, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut interval_set = [(1, 5)].to_interval_set();
+    /// interval_set.symmetric_difference_update(&[(4, 8)].to_interval_set());
+    /// assert_eq!(interval_set, [(1, 3), (6, 8)].to_interval_set());
+    /// ```
+    pub fn symmetric_difference_update(&mut self, other: &IntervalSet<Bound>) {
+        let len = self.intervals.len();
+        symmetric_difference_sweep(
+            &mut self.intervals,
+            len,
+            |buf, idx| buf[idx].clone(),
+            &other.intervals,
+        );
+        self.intervals.drain(..len);
+        self.size = total_size(&self.intervals);
+    }
+
+    /// Writes the union of the interval set and `other` into `out`, replacing its content.
+    /// This is the same as `*out = self.union(other)` but reuses the storage of `out`, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut out = IntervalSet::empty();
+    /// [(1, 3)].to_interval_set().union_into(&[(4, 5), (8, 9)].to_interval_set(), &mut out);
+    /// assert_eq!(out, [(1, 5), (8, 9)].to_interval_set());
+    /// ```
+    pub fn union_into(&self, other: &IntervalSet<Bound>, out: &mut IntervalSet<Bound>) {
+        out.clear();
+        // The union has at most as many intervals as both sets together.
+        out.intervals
+            .reserve_exact(self.intervals.len() + other.intervals.len());
+        let a = &mut self.intervals.iter().cloned().peekable();
+        let b = &mut other.intervals.iter().cloned().peekable();
+        while a.peek().is_some() && b.peek().is_some() {
+            let lower = advance_lower(a, b);
+            out.join_or_push(lower);
+        }
+        out.extend_at_back(a);
+        out.extend_at_back(b);
+    }
+
+    /// Writes the intersection of the interval set and `other` into `out`, replacing its content.
+    /// This is the same as `*out = self.intersection(other)` but reuses the storage of `out`, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut out = [(0, 100)].to_interval_set();
+    /// [(1, 3), (8, 9)].to_interval_set().intersection_into(&[(2, 8)].to_interval_set(), &mut out);
+    /// assert_eq!(out, [(2, 3), (8, 8)].to_interval_set());
+    /// ```
+    pub fn intersection_into(&self, other: &IntervalSet<Bound>, out: &mut IntervalSet<Bound>) {
+        out.clear();
+        // Each interval of the intersection ends at the upper bound of one of the intervals, except the last.
+        if !self.is_empty() && !other.is_empty() {
+            out.intervals
+                .reserve_exact(self.intervals.len() + other.intervals.len() - 1);
+        }
+        let len = self.intervals.len();
+        intersection_sweep(
+            &mut out.intervals,
+            len,
+            |_, idx| self.intervals[idx].clone(),
+            &other.intervals,
+        );
+        out.size = total_size(&out.intervals);
+    }
+
+    /// Writes the values of the interval set that are not in `other` into `out`, replacing its content.
+    /// This is the same as `*out = self.difference(other)` but reuses the storage of `out`, to avoid allocating in a loop.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let mut out = IntervalSet::empty();
+    /// [(1, 9)].to_interval_set().difference_into(&[(3, 4)].to_interval_set(), &mut out);
+    /// assert_eq!(out, [(1, 2), (5, 9)].to_interval_set());
+    /// ```
+    pub fn difference_into(&self, other: &IntervalSet<Bound>, out: &mut IntervalSet<Bound>) {
+        self.difference_intervals_into(&other.intervals, out);
+    }
+}
+
+impl<Bound: Width + Num> Overlap for IntervalSet<Bound> {
+    /// Calculates whether two interval contain any shared values.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (7, 8)].to_interval_set();
+    /// let b = [(4, 6)].to_interval_set();
+    /// assert!(!a.overlap(&b));
+    /// assert!(!b.overlap(&a));
+    ///
+    /// let a = [(1, 3)].to_interval_set();
+    /// let b = [(3, 4), (8, 10)].to_interval_set();
+    /// assert!(a.overlap(&b));
+    /// assert!(b.overlap(&a));
+    /// ```
+    fn overlap(&self, rhs: &IntervalSet<Bound>) -> bool {
+        // Sets with disjoint spans cannot overlap, which avoids the sweep for far-apart sets.
+        if self.is_empty()
+            || rhs.is_empty()
+            || self.upper() < rhs.lower()
+            || rhs.upper() < self.lower()
+        {
+            return false;
+        }
+        let a = &mut self.intervals.iter().cloned().peekable();
+        let b = &mut rhs.intervals.iter().cloned().peekable();
+        advance_to_first_overlapping(a, b)
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Counts the pairs of intervals, one from each interval set, that overlap.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(0, 10)].to_interval_set();
+    /// let b = [(1, 2), (4, 5), (8, 9)].to_interval_set();
+    /// assert_eq!(a.overlap_count(&b), 3);
+    /// assert_eq!(b.overlap_count(&a), 3);
+    ///
+    /// let c = [(2, 4), (12, 15)].to_interval_set();
+    /// assert_eq!(b.overlap_count(&c), 2);
+    /// assert_eq!(b.overlap_count(&IntervalSet::empty()), 0);
+    /// ```
+    pub fn overlap_count(&self, other: &IntervalSet<Bound>) -> usize {
+        let a = &mut self.intervals.iter().cloned().peekable();
+        let b = &mut other.intervals.iter().cloned().peekable();
+        let mut count = 0;
+        while advance_to_first_overlapping(a, b) {
+            count += 1;
+            advance_lub(a, b); // the other interval might overlap the next one.
+        }
+        count
+    }
+}
+
+impl<Bound: Width + Num> Overlap<Bound> for IntervalSet<Bound> {
+    /// Calculates whether a value is included in the interval set.
+    /// This returns the same result as the [`IntervalSet::contains`]
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
+    /// assert!(interval_set.overlap(&3));
+    /// assert!(interval_set.overlap(&8));
+    /// assert!(interval_set.overlap(&9));
+    ///
+    /// assert!(!interval_set.overlap(&1));
+    /// assert!(!interval_set.overlap(&7));
+    /// assert!(!interval_set.overlap(&10));
+    /// ```
+    fn overlap(&self, value: &Bound) -> bool {
+        if let Some((l, u)) = self.find_interval(value) {
+            l == u
+        } else {
+            false
+        }
+    }
+}
+
+impl<Bound: Width + Num> Overlap<Optional<Bound>> for IntervalSet<Bound> {
+    /// Calculates whether an optional value is included in the interval set.
+    /// If the optional empty, this returns false.
+    /// This returns the same result as the [`IntervalSet::contains`]
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 5), (8, 9)].to_interval_set();
+    /// assert!(interval_set.overlap(&Optional::singleton(3)));
+    /// assert!(interval_set.overlap(&Optional::singleton(9)));
+    ///
+    /// assert!(!interval_set.overlap(&Optional::singleton(1)));
+    /// assert!(!interval_set.overlap(&Optional::singleton(10)));
+    ///
+    /// assert!(!interval_set.overlap(&Optional::empty()));
+    /// ```
+    fn overlap(&self, value: &Optional<Bound>) -> bool {
+        value.as_ref().map_or(false, |b| self.overlap(b))
+    }
+}
+
+macro_rules! primitive_interval_set_overlap
+{
+  ( $( $source:ty ),* ) =>
+  {$(
+    impl Overlap<IntervalSet<$source>> for $source {
+      #[doc = concat!(
+        r#"
+        Calculates whether a value is included in an interval set.
+        ```
+        # use interval::prelude::*;
+        let interval_set: IntervalSet<"#, stringify!($source), r#"> = [(3, 5), (8, 9)].to_interval_set();
+        assert!((3 as "#, stringify!($source), r#").overlap(&interval_set));
+        assert!((8 as "#, stringify!($source), r#").overlap(&interval_set));
+        assert!((9 as "#, stringify!($source), r#").overlap(&interval_set));
+        ///
+        assert!(!(1 as "#, stringify!($source), r#").overlap(&interval_set));
+        assert!(!(7 as "#, stringify!($source), r#").overlap(&interval_set));
+        assert!(!(10 as "#, stringify!($source), r#").overlap(&interval_set));
+        ```
+        "#
+      )]
+      fn overlap(&self, other: &IntervalSet<$source>) -> bool {
+        other.overlap(self)
+      }
+    }
+  )*}
+}
+
+primitive_interval_set_overlap!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+impl<Bound: Width + Num> Disjoint for IntervalSet<Bound> {
+    /// Calculates whether two interval do *not* contain any shared values.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (7, 8)].to_interval_set();
+    /// let b = [(4, 6)].to_interval_set();
+    /// assert!(a.is_disjoint(&b));
+    /// assert!(b.is_disjoint(&a));
+    ///
+    /// let a = [(1, 3)].to_interval_set();
+    /// let b = [(3, 4), (8, 10)].to_interval_set();
+    /// assert!(!a.is_disjoint(&b));
+    /// assert!(!b.is_disjoint(&a));
+    /// ```
+    fn is_disjoint(&self, rhs: &IntervalSet<Bound>) -> bool {
+        !self.overlap(rhs)
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    /// Calculates whether an interval does *not* share any value with the interval set, in logarithmic time.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 3), (7, 8)].to_interval_set();
+    /// assert!(interval_set.is_disjoint_interval(&Interval::new(4, 6)));
+    /// assert!(!interval_set.is_disjoint_interval(&Interval::new(3, 6)));
+    /// assert!(interval_set.is_disjoint_interval(&Interval::empty()));
+    /// ```
+    pub fn is_disjoint_interval(&self, i: &Interval<Bound>) -> bool {
+        if i.is_empty() {
+            return true;
+        }
+        let idx = self.intervals.partition_point(|x| x.upper() < i.lower());
+        idx == self.intervals.len() || self.intervals[idx].lower() > i.upper()
+    }
+
+    /// Calculates whether an interval of the set is at most `window` values away from an interval of `other`,
+    /// i.e. whether the two sets would overlap if the gaps of at most `window` values between them were filled.
+    /// With a `window` of zero, adjacent intervals such as `(1, 3)` and `(4, 5)` are accepted as well as overlapping ones.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 3), (20, 25)].to_interval_set();
+    /// let b = [(6, 8), (40, 41)].to_interval_set();
+    /// assert!(!a.overlaps_within(&b, 1));
+    /// assert!(a.overlaps_within(&b, 2));
+    /// ```
+    /// Panics if `window` is negative.
+    pub fn overlaps_within(&self, other: &IntervalSet<Bound>, window: Bound) -> bool {
+        assert!(window >= Bound::zero(), "The window must not be negative.");
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (x, y) = (&self.intervals[i], &other.intervals[j]);
+            // The interval ending first cannot come closer to the next intervals of the other set.
+            if x.upper() < y.lower() {
+                if gap_at_most(x.upper(), y.lower(), &window) {
+                    return true;
+                }
+                i += 1;
+            } else if y.upper() < x.lower() {
+                if gap_at_most(y.upper(), x.lower(), &window) {
+                    return true;
+                }
+                j += 1;
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<Bound: Width + Num> ShrinkLeft for IntervalSet<Bound>
+where
+    <Bound as Width>::Output: Clone,
+{
+    /// Updates the lower bound of an interval set to be greater than or equal to a value.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(4, 5), (8, 8)].to_interval_set();
+    /// assert_eq!(interval_set.shrink_left(2), interval_set);
+    /// assert_eq!(interval_set.shrink_left(4), interval_set);
+    /// assert_eq!(interval_set.shrink_left(5), [(5, 5), (8, 8)].to_interval_set());
+    /// assert_eq!(interval_set.shrink_left(7), IntervalSet::singleton(8));
+    /// assert_eq!(interval_set.shrink_left(8), IntervalSet::singleton(8));
+    /// assert_eq!(interval_set.shrink_left(9), IntervalSet::empty());
+    /// ```
+    fn shrink_left(&self, lb: Bound) -> IntervalSet<Bound> {
+        if let Some((left, _)) = self.find_interval(&lb) {
+            let mut res = IntervalSet::empty();
+            if self.intervals[left].upper() >= lb {
+                res.push(Interval::new(lb, self.intervals[left].upper()));
+            }
+            for i in (left + 1)..self.intervals.len() {
+                res.push(self.intervals[i].clone());
+            }
+            res
+        } else if self.is_empty() || lb > self.back().upper() {
+            IntervalSet::empty()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl<Bound: Width + Num> ShrinkRight for IntervalSet<Bound>
+where
+    <Bound as Width>::Output: Clone,
+{
+    /// Updates the upper bound of an interval set to be less than or equal to a value.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
+    /// assert_eq!(interval_set.shrink_right(9), interval_set);
+    /// assert_eq!(interval_set.shrink_right(8), interval_set);
+    /// assert_eq!(interval_set.shrink_right(7), [(3, 3), (7, 7)].to_interval_set());
+    /// assert_eq!(interval_set.shrink_right(6), IntervalSet::singleton(3));
+    /// assert_eq!(interval_set.shrink_right(3), IntervalSet::singleton(3));
+    /// assert_eq!(interval_set.shrink_right(2), IntervalSet::empty());
+    /// ```
+    fn shrink_right(&self, ub: Bound) -> IntervalSet<Bound> {
+        if let Some((_, right)) = self.find_interval(&ub) {
+            let mut res = IntervalSet::empty();
+            for i in 0..right {
+                res.push(self.intervals[i].clone());
+            }
+            if self.intervals[right].lower() <= ub {
+                res.push(Interval::new(self.intervals[right].lower(), ub));
+            }
+            res
+        } else if self.is_empty() || ub < self.front().lower() {
+            IntervalSet::empty()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    // Counts the calls to `IntervalSet::is_subset` to test the work done by `entail`.
+    static IS_SUBSET_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+impl<Bound: Width + Num> Subset for IntervalSet<Bound> {
+    /// Calculates whether one interval set is contained in another.
+    /// The empty interval set is a subset of everything.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
+    /// assert!(interval_set.is_subset(&[(3, 8)].to_interval_set()));
+    /// assert!(interval_set.is_subset(&[(3, 4), (7, 9)].to_interval_set()));
+    /// assert!(interval_set.is_subset(&interval_set));
+    ///
+    /// assert!(!interval_set.is_subset(&[(3, 3)].to_interval_set()));
+    /// assert!(!interval_set.is_subset(&[(7, 9)].to_interval_set()));
+    /// assert!(!interval_set.is_subset(&[(3, 3), (8, 9)].to_interval_set()));
+    ///
+    /// assert!(IntervalSet::<usize>::empty().is_subset(&IntervalSet::empty()));
+    /// assert!(IntervalSet::empty().is_subset(&interval_set));
+    /// ```
+    fn is_subset(&self, other: &IntervalSet<Bound>) -> bool {
+        #[cfg(test)]
+        IS_SUBSET_CALLS.with(|calls| calls.set(calls.get() + 1));
+        if self.is_empty() {
+            true
+        } else if self.size() > other.size() || !self.span().is_subset(&other.span()) {
+            false
+        } else {
+            // The intervals of `self` are sorted, so the search window in `other` only shrinks from the left.
+            // Intervals of `self` nested in the same interval of `other` do not need a new search.
+            let mut left = 0;
+            let right = other.intervals.len() - 1;
+            for interval in &self.intervals {
+                if interval.upper() <= other.intervals[left].upper() {
+                    if interval.lower() < other.intervals[left].lower() {
+                        return false;
+                    }
+                    continue;
+                }
+                // `interval` starts before the next interval of `other`, so it crosses a gap.
+                if left == right || interval.lower() < other.intervals[left + 1].lower() {
+                    return false;
+                }
+                let (l, r) = other.find_interval_between(&interval.lower(), left + 1, right);
+                if l == r && interval.is_subset(&other.intervals[l]) {
+                    left = l;
+                } else {
+                    return false;
+                }
+            }
+            true
+        }
+    }
+}
+
+impl<Bound: Width + Num> ProperSubset for IntervalSet<Bound> {
+    /// Calculates whether one interval set is contained in another,
+    /// but they are not equal.
+    /// The empty interval set is a proper subset of everything, except itself.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(3, 3), (7, 8)].to_interval_set();
+    /// assert!(interval_set.is_proper_subset(&[(3, 8)].to_interval_set()));
+    /// assert!(interval_set.is_proper_subset(&[(3, 4), (7, 9)].to_interval_set()));
+    ///
+    /// assert!(!interval_set.is_proper_subset(&interval_set));
+    /// assert!(!interval_set.is_proper_subset(&[(3, 3)].to_interval_set()));
+    /// assert!(!interval_set.is_proper_subset(&[(7, 9)].to_interval_set()));
+    /// assert!(!interval_set.is_proper_subset(&[(3, 3), (8, 9)].to_interval_set()));
+    ///
+    /// assert!(IntervalSet::empty().is_proper_subset(&interval_set));
+    /// assert!(!IntervalSet::<usize>::empty().is_proper_subset(&IntervalSet::empty()));
+    /// ```
+    fn is_proper_subset(&self, other: &IntervalSet<Bound>) -> bool {
+        self.is_subset(other) && self.size() != other.size()
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Add for IntervalSet<Bound>, add);
+
+impl<'a, 'b, Bound: Num + Width> Add<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates all values that could result in the addition of two items from each interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 2), (5, 6)].to_interval_set();
+    /// let b = [(1, 1), (4, 5)].to_interval_set();
+    /// assert_eq!(a + b, [(2, 3), (5, 7), (9, 11)].to_interval_set());
+    /// ```
+    /// This method preserves empty interval sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 1), (4, 5)].to_interval_set();
+    /// let b = IntervalSet::empty();
+    /// assert!((a + b).is_empty());
+    /// ```
+    fn add(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.for_all_pairs(other, |i, j| i + j)
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width+Clone> Add for IntervalSet<Bound>, add, Bound);
+
+impl<'a, 'b, Bound: Num + Width + Clone> Add<&'b Bound> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Adds a constant to an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(3, 3), (7, 8)].to_interval_set() + 2, [(5, 5), (9, 10)].to_interval_set());
+    /// ```
+    /// This method preserves empty interval sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::empty() + 4).is_empty());
+    /// ```
+    /// It is not possible to add an interval set to a constant.
+    /// ```compile_fail
+    /// # use interval::prelude::*;
+    /// let _ = 4 + IntervalSet::new(5, 9); // doesn't compile
+    /// ```
+    fn add(self, other: &Bound) -> IntervalSet<Bound> {
+        self.stable_map(|x| x + other.clone())
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Add for IntervalSet<Bound>, add, Interval<Bound>);
+
+impl<Bound: Num + Width> Add<&Interval<Bound>> for &IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Adds an interval to each interval of the set, which is the same as adding the interval set containing it.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6), (20, 20)].to_interval_set();
+    /// assert_eq!(interval_set + Interval::new(0, 2), [(1, 8), (20, 22)].to_interval_set());
+    /// ```
+    /// This method preserves empty intervals.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::new(1, 2) + Interval::empty()).is_empty());
+    /// ```
+    fn add(self, other: &Interval<Bound>) -> IntervalSet<Bound> {
+        if other.is_empty() {
+            return IntervalSet::empty();
+        }
+        // The lower bounds are all shifted by the same amount, so the intervals stay sorted.
+        let mut res = IntervalSet::empty();
+        res.extend_at_back(self.intervals.iter().map(|i| i + other));
+        res
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Sub for IntervalSet<Bound>, sub);
+
+impl<'a, 'b, Bound: Num + Width> Sub<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates the arithmetic difference of two interval sets, containing `x - y` for every `x` in `self` and `y` in `other`.
+    /// For the set difference, use [`IntervalSet::set_minus`] or [`Difference::difference`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(0, 10)].to_interval_set();
+    /// let b = [(2, 3)].to_interval_set();
+    /// assert_eq!(&a - &b, [(-3, 8)].to_interval_set());
+    /// ```
+    fn sub(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.for_all_pairs(other, |i, j| i - j)
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width+Clone> Sub for IntervalSet<Bound>, sub, Bound);
+
+impl<'a, 'b, Bound: Num + Width + Clone> Sub<&'b Bound> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Subtracts a constant from an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(3, 3), (7, 8)].to_interval_set() - 2, [(1, 1), (5, 6)].to_interval_set());
+    /// ```
+    /// This method preserves empty interval sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::empty() - 4).is_empty());
+    /// ```
+    /// It is not possible to substract an interval set from a constant.
+    /// ```compile_fail
+    /// # use interval::prelude::*;
+    /// let _ = 10 - IntervalSet::new(5, 9); // doesn't compile
+    /// ```
+    fn sub(self, other: &Bound) -> IntervalSet<Bound> {
+        self.stable_map(|x| x - other.clone())
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Sub for IntervalSet<Bound>, sub, Interval<Bound>);
+
+impl<Bound: Num + Width> Sub<&Interval<Bound>> for &IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Subtracts an interval from each interval of the set, which is the same as subtracting the interval set containing it.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6), (20, 20)].to_interval_set();
+    /// assert_eq!(interval_set - Interval::new(0, 2), [(-1, 6), (18, 20)].to_interval_set());
+    /// ```
+    /// This method preserves empty intervals.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::new(1, 2) - Interval::empty()).is_empty());
+    /// ```
+    fn sub(self, other: &Interval<Bound>) -> IntervalSet<Bound> {
+        if other.is_empty() {
+            return IntervalSet::empty();
+        }
+        // The lower bounds are all shifted by the same amount, so the intervals stay sorted.
+        let mut res = IntervalSet::empty();
+        res.extend_at_back(self.intervals.iter().map(|i| i - other));
+        res
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Mul for IntervalSet<Bound>, mul);
+
+impl<'a, 'b, Bound: Num + Width> Mul<&'b IntervalSet<Bound>> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Calculates all values that could result in the multiplication of two items from each interval set.
+    /// Caution: the resulting interval set is an over-approxmation for the same reason as [`Interval::mul`](../interval/struct.Interval.html#method.mul-3).
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 2), (5, 6)].to_interval_set();
+    /// let b = [(0, 0), (3, 4)].to_interval_set();
+    /// assert_eq!(a * b, [(0, 0), (3, 8), (15, 24)].to_interval_set());
+    /// ```
+    /// This method preserves empty interval sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::empty() * [(0, 0), (3, 4)].to_interval_set()).is_empty());
+    /// ```
+    fn mul(self, other: &IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.for_all_pairs(other, |i, j| i * j)
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width+Clone> Mul for IntervalSet<Bound>, mul, Bound);
+
+impl<'a, 'b, Bound: Num + Width + Clone> Mul<&'b Bound> for &'a IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Multiplies an interval set by a constant.
+    /// Caution: the resulting interval set is an over-approxmation for the same reason as [`Interval::mul`](../interval/struct.Interval.html#method.mul-7).
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(1, 2), (5, 6)].to_interval_set() * 2, [(2, 4), (10, 12)].to_interval_set());
+    /// ```
+    /// A negative constant reverses the order of the intervals.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(1, 2), (5, 6)].to_interval_set() * -2, [(-12, -10), (-4, -2)].to_interval_set());
+    /// ```
+    /// This method preserves empty interval sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::empty() * 11).is_empty());
+    /// ```
+    /// It is not possible to multiply a constant by an interval set.
+    /// ```compile_fail
+    /// # use interval::prelude::*;
+    /// let _ = 4 * IntervalSet::new(5, 9); // doesn't compile
+    /// ```
+    fn mul(self, other: &Bound) -> IntervalSet<Bound> {
+        if self.is_empty() {
+            IntervalSet::empty()
+        } else if other == &Bound::zero() {
+            IntervalSet::singleton(Bound::zero())
+        } else if other == &Bound::one() {
+            self.clone()
+        } else {
+            // A negative factor reverses the order of the intervals.
+            let reversed = other < &Bound::zero();
+            self.map(reversed, |i| i * other.clone())
+        }
+    }
+}
+
+forward_all_binop!(impl<Bound: +Num+Width> Mul for IntervalSet<Bound>, mul, Interval<Bound>);
+
+impl<Bound: Num + Width> Mul<&Interval<Bound>> for &IntervalSet<Bound> {
+    type Output = IntervalSet<Bound>;
+
+    /// Multiplies each interval of the set by an interval, which is the same as multiplying by the interval set containing it.
+    /// Caution: the resulting interval set is an over-approxmation for the same reason as [`Interval::mul`](../interval/struct.Interval.html#method.mul-3).
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// assert_eq!(interval_set * Interval::new(-2, -1), [(-12, -5), (-4, -1)].to_interval_set());
+    /// ```
+    /// This method preserves empty intervals.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert!((IntervalSet::new(1, 2) * Interval::empty()).is_empty());
+    /// ```
+    fn mul(self, other: &Interval<Bound>) -> IntervalSet<Bound> {
+        // Unlike the addition, a negative factor changes the order of the intervals.
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| intervals.extend(self.intervals.iter().map(|i| i * other)));
+        res
+    }
+}
+
+impl<Bound: Num + Width> IntervalSet<Bound> {
+    /// Multiplies two interval sets like [`Mul`], and also reports whether the product is known to be exact,
+    /// i.e. whether every value of the result is the product of a value of `self` and a value of `other`.
+    ///
+    /// The product of two intervals is exact only when one of them is contained in `[-1, 1]` (and, unless it is a singleton,
+    /// the other touches `[-1, 1]`), or when both are singletons.
+    /// In particular, non-negative operands are not enough: `[1, 2] * [1, 2]` is `[1, 4]` but 3 is not a product.
+    /// The flag is conservative: `true` guarantees an exact result, whereas `false` means it may be an over-approximation,
+    /// even though the union of the products can sometimes fill the gaps.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 2)].to_interval_set();
+    /// assert_eq!(a.mul_exact_if_possible(&a), ([(1, 4)].to_interval_set(), false));
+    /// let b = [(-1, 1)].to_interval_set();
+    /// assert_eq!(b.mul_exact_if_possible(&[(-3, 5)].to_interval_set()), ([(-5, 5)].to_interval_set(), true));
+    /// ```
+    pub fn mul_exact_if_possible(&self, other: &IntervalSet<Bound>) -> (IntervalSet<Bound>, bool) {
+        let exact = self
+            .intervals
+            .iter()
+            .all(|i| other.intervals.iter().all(|j| is_exact_product(i, j)));
+        (self * other, exact)
+    }
+}
+
+impl<Bound: Num + Width + CheckedMul> IntervalSet<Bound> {
+    /// Multiplies two interval sets like [`Mul`], or returns `None` if a bound of a product overflows
+    /// or does not fit within [`Width::min_value`] and [`Width::max_value`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let a = [(1, 2), (5, 6)].to_interval_set();
+    /// let b = [(0, 0), (3, 4)].to_interval_set();
+    /// assert_eq!(a.checked_mul(&b), Some([(0, 0), (3, 8), (15, 24)].to_interval_set()));
+    /// let big = IntervalSet::new(0, i32::MAX / 2);
+    /// assert_eq!(big.checked_mul(&IntervalSet::new(1, 3)), None);
+    /// ```
+    pub fn checked_mul(&self, other: &IntervalSet<Bound>) -> Option<IntervalSet<Bound>> {
+        let mut products = Vec::with_capacity(self.intervals.len() * other.intervals.len());
+        for i in &self.intervals {
+            for j in &other.intervals {
+                products.push(checked_product(i, j)?);
+            }
+        }
+        let mut res = IntervalSet::empty();
+        res.set_bounds(|intervals| intervals.extend(products));
+        Some(res)
+    }
+
+    /// Multiplies an interval set by a constant like [`Mul`], or returns `None` if a bound of the product overflows
+    /// or does not fit within [`Width::min_value`] and [`Width::max_value`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 2), (5, 6)].to_interval_set();
+    /// assert_eq!(interval_set.checked_mul_bound(&2), Some([(2, 4), (10, 12)].to_interval_set()));
+    /// assert_eq!(interval_set.checked_mul_bound(&i32::MAX), None);
+    /// ```
+    pub fn checked_mul_bound(&self, other: &Bound) -> Option<IntervalSet<Bound>> {
+        self.checked_mul(&IntervalSet::singleton(other.clone()))
+    }
+}
+
+/// Multiplies two non-empty intervals, or returns `None` if a bound of the product is not representable.
+fn checked_product<Bound>(a: &Interval<Bound>, b: &Interval<Bound>) -> Option<Interval<Bound>>
+where
+    Bound: Num + Width + CheckedMul,
+{
+    let (a_lower, a_upper) = (a.lower_ref(), a.upper_ref());
+    let (b_lower, b_upper) = (b.lower_ref(), b.upper_ref());
+    let corners = [
+        a_lower.checked_mul(b_lower)?,
+        a_lower.checked_mul(b_upper)?,
+        a_upper.checked_mul(b_lower)?,
+        a_upper.checked_mul(b_upper)?,
+    ];
+    let lower = IntoIterator::into_iter(corners.clone()).min()?;
+    let upper = IntoIterator::into_iter(corners).max()?;
+    if lower < <Bound as Width>::min_value() || upper > <Bound as Width>::max_value() {
+        None
+    } else {
+        Some(Interval::new(lower, upper))
+    }
+}
+
+/// Checks whether the product of two non-empty intervals contains only values that are products of their elements.
+/// If neither interval is contained in `[-1, 1]`, the extremal product `p * q` has `|p|, |q| >= 2`,
+/// and the value next to it towards zero cannot be a product, unless both intervals are singletons.
+fn is_exact_product<Bound: Num + Width>(a: &Interval<Bound>, b: &Interval<Bound>) -> bool {
+    // `v + 1` is only computed for negative `v`, so it cannot overflow.
+    let at_least_minus_one = |v: Bound| v >= Bound::zero() || v + Bound::one() >= Bound::zero();
+    let within_one =
+        |i: &Interval<Bound>| i.upper() <= Bound::one() && at_least_minus_one(i.lower());
+    let touches_one =
+        |i: &Interval<Bound>| i.lower() <= Bound::one() && at_least_minus_one(i.upper());
+    let is_singleton = |i: &Interval<Bound>| i.lower() == i.upper();
+    (is_singleton(a) && is_singleton(b))
+        || (within_one(a) && (is_singleton(a) || touches_one(b)))
+        || (within_one(b) && (is_singleton(b) || touches_one(a)))
+}
+
+pub trait ToIntervalSet<Bound>
+where
+    Bound: Width,
+{
+    /// Converts a value to an interval set.
+    /// For example,
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!((3, 4).to_interval_set(), IntervalSet::new(3, 4));
+    /// assert_eq!([(2, 5), (7, 8)].to_interval_set(), IntervalSet::union(&IntervalSet::new(2, 5), &IntervalSet::new(7, 8)));
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound>;
+
+    /// Converts a value to an interval set, also merging the intervals separated by at most `gap` missing values.
+    /// With a `gap` of zero, this is the same as [`ToIntervalSet::to_interval_set`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(vec![(0, 2), (5, 7)].to_interval_set_with_gap(2), IntervalSet::new(0, 7));
+    /// assert_eq!(vec![(0, 2), (6, 7)].to_interval_set_with_gap(2), [(0, 2), (6, 7)].to_interval_set());
+    /// ```
+    fn to_interval_set_with_gap(self, gap: Bound) -> IntervalSet<Bound>
+    where
+        Self: Sized,
+        Bound: Num,
+    {
+        let interval_set = self.to_interval_set();
+        IntervalSet::merge_with(interval_set.intervals, |a, b| {
+            gap_at_most(a.upper(), b.lower(), &gap)
+        })
+    }
+}
+
+impl<Bound: Width + Num> ToIntervalSet<Bound> for (Bound, Bound) {
+    /// Converts a tuple to an interval set using the first element as the lower bound
+    /// and second element as the upper bound.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!((2, 6).to_interval_set(), IntervalSet::new(2, 6));
+    /// ```
+    /// The first and second elements need the same type.
+    /// ```compile_fail
+    /// # use interval::prelude::*;
+    /// let _ = (8 as u8, 9 as i8).to_interval_set(); // doesn't compile
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        [self].to_interval_set()
+    }
+}
+
+impl<Bound> ToIntervalSet<Bound> for Vec<(Bound, Bound)>
+where
+    Bound: Width + Num,
+{
+    /// Converts a vector of intervals to an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(vec![(2, 5)].to_interval_set().interval_count(), 1);
+    /// assert_eq!(vec![(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
+    /// assert!(Vec::<(usize, usize)>::new().to_interval_set().is_empty());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        let mut intervals = IntervalSet::empty();
+        let mut to_add: Vec<_> = self.into_iter().map(|i| i.to_interval()).collect();
+        to_add.sort_unstable_by_key(|i| i.lower());
+        intervals.extend_at_back(to_add);
+        intervals
+    }
+}
+
+impl<Bound> ToIntervalSet<Bound> for &[(Bound, Bound)]
+where
+    Bound: Width + Num + Copy,
+{
+    /// Converts an array to an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(2, 5)].to_interval_set().interval_count(), 1);
+    /// assert_eq!([(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
+    /// assert!(<&[(usize, usize)]>::default().to_interval_set().is_empty());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        self.to_vec().to_interval_set()
+    }
+}
+
+impl<Bound, const N: usize> ToIntervalSet<Bound> for [(Bound, Bound); N]
+where
+    Bound: Width + Num + Clone,
+{
+    /// Converts a fixed-length array to an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([(2, 5)].to_interval_set().interval_count(), 1);
+    /// assert_eq!([(1, 5), (11, 20)].to_interval_set().interval_count(), 2);
+    /// assert!(([] as [(usize, usize); 0]).to_interval_set().is_empty());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        self.to_vec().to_interval_set()
+    }
+}
+
+impl<Bound: Width + Num> ToIntervalSet<Bound> for RangeInclusive<Bound> {
+    /// Converts an inclusive range to an interval set.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!((0..=5).to_interval_set(), IntervalSet::new(0, 5));
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        [self].to_interval_set()
+    }
+}
+
+impl<Bound: Width + Num, const N: usize> ToIntervalSet<Bound> for [RangeInclusive<Bound>; N] {
+    /// Converts a fixed-length array of inclusive ranges, in any order, to an interval set.
+    /// Overlapping or adjacent ranges are merged, and empty ranges are ignored.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!([7..=9, 0..=3, 2..=5].to_interval_set(), [(0, 5), (7, 9)].to_interval_set());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        IntervalSet::from_ranges(self)
+    }
+}
+
+impl<Bound: Width + Num + Hash> ToIntervalSet<Bound> for HashSet<Bound> {
+    /// Converts a hash set of values to the interval set containing them.
+    /// Consecutive values are merged in a single interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// # use std::collections::HashSet;
+    /// let values: HashSet<i32> = vec![9, 1, 3, 2].into_iter().collect();
+    /// assert_eq!(values.to_interval_set(), [(1, 3), (9, 9)].to_interval_set());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        IntervalSet::from_values(self)
+    }
+}
+
+impl<Bound: Width + Num> ToIntervalSet<Bound> for BTreeSet<Bound> {
+    /// Converts a B-tree set of values to the interval set containing them, without sorting them again.
+    /// Consecutive values are merged in a single interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// # use std::collections::BTreeSet;
+    /// let values: BTreeSet<i32> = vec![9, 1, 3, 2].into_iter().collect();
+    /// assert_eq!(values.to_interval_set(), [(1, 3), (9, 9)].to_interval_set());
+    /// ```
+    fn to_interval_set(self) -> IntervalSet<Bound> {
+        IntervalSet::from_sorted_values(self)
+    }
+}
+
+/// Creates an interval set from a list of `(lower, upper)` tuples, like `vec!` does for vectors.
+/// `iset![(1, 2), (5, 6)]` is the same as `[(1, 2), (5, 6)].to_interval_set()`, and `iset![]` is the empty set.
+/// ```
+/// # use interval::prelude::*;
+/// let empty: IntervalSet<i32> = iset![];
+/// assert!(empty.is_empty());
+/// assert_eq!(iset![(3, 7)], IntervalSet::new(3, 7));
+/// assert_eq!(iset![(5, 6), (1, 2), (3, 3)], [(1, 3), (5, 6)].to_interval_set());
+/// ```
+/// The macro does not need the prelude to be imported:
+/// ```
+/// use interval::{iset, IntervalSet};
+/// let empty: IntervalSet<i32> = iset![];
+/// assert_eq!(empty, iset![]);
+/// assert_ne!(empty, iset![(3, 7)]);
+/// assert_eq!(iset![(1, 2), (3, 3)], iset![(1, 3)]);
+/// ```
+#[macro_export]
+macro_rules! iset {
+    () => {
+        <$crate::IntervalSet<_> as $crate::prelude::Empty>::empty()
+    };
+    ($($interval:expr),+ $(,)?) => {
+        $crate::interval_set::ToIntervalSet::to_interval_set([$($interval),+])
+    };
+}
+
+impl<Bound> From<&[Bound]> for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Converts a slice of values, in any order and possibly duplicated, to the interval set containing them.
+    /// Consecutive values are merged in a single interval.
+    /// ```
+    /// # use interval::prelude::*;
+    /// let values = [5, 1, 2, 3, 5, 9];
+    /// assert_eq!(IntervalSet::from(&values[..]), [(1, 3), (5, 5), (9, 9)].to_interval_set());
+    /// ```
+    fn from(values: &[Bound]) -> IntervalSet<Bound> {
+        IntervalSet::from_values(values.iter().cloned())
+    }
+}
+
+impl<Bound: Display + Width + Num> Display for IntervalSet<Bound>
+where
+    <Bound as Width>::Output: Display,
+{
+    /// Formats an interval set.
+    /// Empty interval sets are displayed as the empty set "{}".
+    /// Single intervals are displayed as the isolated interval.
+    /// Combined intervals are displayed as a sorted set of intervals.
+    /// See [`Interval::fmt`](../interval/struct.Interval.html#method.fmt-1) for more detail on how intervals are formatted.
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(format!("{}", [(3, 5)].to_interval_set()), "[3..5]");
+    /// assert_eq!(format!("{}", [(4, 4), (8, 9)].to_interval_set()), "{[4..4][8..9]}");
+    /// assert_eq!(format!("{}", IntervalSet::<u32>::empty()), "{}");
+    /// ```
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        if self.intervals.len() == 1 {
+            self.intervals[0].fmt(formatter)
+        } else {
+            formatter.write_str("{")?;
+            for interval in &self.intervals {
+                formatter.write_fmt(format_args!("{}", interval))?;
+            }
+            formatter.write_str("}")
+        }
+    }
+}
+
+impl<Bound: Width + Num> IntervalSet<Bound> {
+    // Formats the intervals with the same structure as `Display`, using `fmt_interval` for each interval.
+    fn fmt_intervals<F>(&self, formatter: &mut Formatter, fmt_interval: F) -> Result<(), Error>
+    where
+        F: Fn(&Interval<Bound>, &mut Formatter) -> Result<(), Error>,
+    {
+        if self.is_empty() {
+            formatter.write_str("{}")
+        } else if self.intervals.len() == 1 {
+            fmt_interval(&self.intervals[0], formatter)
+        } else {
+            formatter.write_str("{")?;
+            for interval in &self.intervals {
+                fmt_interval(interval, formatter)?;
+            }
+            formatter.write_str("}")
+        }
+    }
+}
+
+impl<Bound: LowerHex + Width + Num> LowerHex for IntervalSet<Bound> {
+    /// Formats an interval set with the bounds in lower-case hexadecimal.
+    /// The structure is the same as [`IntervalSet::fmt`](#method.fmt).
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(format!("{:x}", [(0x1000, 0x1fff)].to_interval_set()), "[0x1000..0x1fff]");
+    /// assert_eq!(format!("{:x}", [(0xa0, 0xaf), (0xc0, 0xc0)].to_interval_set()), "{[0xa0..0xaf][0xc0..0xc0]}");
+    /// assert_eq!(format!("{:x}", IntervalSet::<u32>::empty()), "{}");
+    /// ```
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        self.fmt_intervals(formatter, |i, f| {
+            f.write_fmt(format_args!("[{:#x}..{:#x}]", i.lower(), i.upper()))
+        })
+    }
+}
+
+impl<Bound: UpperHex + Width + Num> UpperHex for IntervalSet<Bound> {
+    /// Formats an interval set with the bounds in upper-case hexadecimal.
+    /// The structure is the same as [`IntervalSet::fmt`](#method.fmt).
+    /// ```
+    /// # use interval::prelude::*;
+    /// assert_eq!(format!("{:X}", [(0x1000, 0x1fff)].to_interval_set()), "[0x1000..0x1FFF]");
+    /// assert_eq!(format!("{:X}", [(0xa0, 0xaf), (0xc0, 0xc0)].to_interval_set()), "{[0xA0..0xAF][0xC0..0xC0]}");
+    /// assert_eq!(format!("{:X}", IntervalSet::<u32>::empty()), "{}");
+    /// ```
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        self.fmt_intervals(formatter, |i, f| {
+            f.write_fmt(format_args!("[{:#X}..{:#X}]", i.lower(), i.upper()))
+        })
+    }
+}
+
+/// Formats an interval set with custom separators, see [`IntervalSet::display_with`].
+pub struct IntervalSetDisplay<'a, Bound: Width> {
+    set: &'a IntervalSet<Bound>,
+    separator: &'a str,
+    range_sep: &'a str,
+}
+
+impl<Bound: Width> IntervalSet<Bound> {
+    /// Formats an interval set by writing each interval as `lower{range_sep}upper`, and separating the intervals with `separator`.
+    /// Empty interval sets are displayed as the empty set "{}".
+    /// ```
+    /// # use interval::prelude::*;
+    /// let interval_set = [(1, 5), (8, 9)].to_interval_set();
+    /// assert_eq!(format!("{}", interval_set.display_with(", ", "..")), "1..5, 8..9");
+    /// assert_eq!(format!("{}", interval_set.display_with(";", "-")), "1-5;8-9");
+    /// assert_eq!(format!("{}", IntervalSet::new(3, 5).display_with(", ", "..")), "3..5");
+    /// assert_eq!(format!("{}", IntervalSet::<u32>::empty().display_with(", ", "..")), "{}");
+    /// ```
+    pub fn display_with<'a>(
+        &'a self,
+        separator: &'a str,
+        range_sep: &'a str,
+    ) -> IntervalSetDisplay<'a, Bound> {
+        IntervalSetDisplay {
+            set: self,
+            separator,
+            range_sep,
+        }
+    }
+}
+
+impl<Bound: Display + Width + Num> Display for IntervalSetDisplay<'_, Bound> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        if self.set.is_empty() {
+            return formatter.write_str("{}");
+        }
+        for (idx, interval) in self.set.intervals.iter().enumerate() {
+            if idx > 0 {
+                formatter.write_str(self.separator)?;
+            }
+            formatter.write_fmt(format_args!(
+                "{}{}{}",
+                interval.lower(),
+                self.range_sep,
+                interval.upper()
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+impl<Bound> Join for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    fn join(self, other: IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.intersection(&other)
+    }
+}
+
+impl<Bound> Meet for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    fn meet(self, other: IntervalSet<Bound>) -> IntervalSet<Bound> {
+        self.union(&other)
+    }
+}
+
+impl<Bound> Entailment for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    fn entail(&self, other: &IntervalSet<Bound>) -> SKleene {
+        // A set can only be a subset of a set at least as large, and two sets of the same size
+        // are subsets of each other only when they are equal: a single inclusion test decides.
+        match self.size().cmp(&other.size()) {
+            Ordering::Less | Ordering::Equal if self.is_subset(other) => SKleene::True,
+            Ordering::Greater if other.is_subset(self) => SKleene::False,
+            _ => SKleene::Unknown,
+        }
+    }
+}
+
+impl<Bound> Sum for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Calculates the union of all the interval sets, starting from the empty interval set.
+    /// This folds the interval sets with [`Meet::meet`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let sets = vec![[(1, 2)].to_interval_set(), [(2, 5)].to_interval_set(), [(8, 9)].to_interval_set()];
+    /// assert_eq!(sets.into_iter().sum::<IntervalSet<_>>(), [(1, 5), (8, 9)].to_interval_set());
+    /// assert_eq!(Vec::<IntervalSet<i32>>::new().into_iter().sum::<IntervalSet<_>>(), IntervalSet::empty());
+    /// ```
+    fn sum<I>(iter: I) -> IntervalSet<Bound>
+    where
+        I: Iterator<Item = IntervalSet<Bound>>,
+    {
+        iter.fold(IntervalSet::empty(), |acc, x| acc.union(&x))
+    }
+}
+
+impl<Bound> Product for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    /// Calculates the intersection of all the interval sets, starting from the whole interval set.
+    /// This folds the interval sets with [`Join::join`].
+    /// ```
+    /// # use interval::prelude::*;
+    /// let sets = vec![[(1, 6)].to_interval_set(), [(2, 5), (7, 9)].to_interval_set(), [(4, 9)].to_interval_set()];
+    /// assert_eq!(sets.into_iter().product::<IntervalSet<_>>(), [(4, 5)].to_interval_set());
+    /// assert_eq!(Vec::<IntervalSet<i32>>::new().into_iter().product::<IntervalSet<_>>(), IntervalSet::whole());
+    /// ```
+    fn product<I>(iter: I) -> IntervalSet<Bound>
+    where
+        I: Iterator<Item = IntervalSet<Bound>>,
+    {
+        iter.fold(IntervalSet::whole(), |acc, x| acc.intersection(&x))
+    }
+}
+
+impl<Bound> Top for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    fn top() -> IntervalSet<Bound> {
+        IntervalSet::empty()
+    }
+}
+
+impl<Bound> Bot for IntervalSet<Bound>
+where
+    Bound: Width + Num,
+{
+    fn bot() -> IntervalSet<Bound> {
+        IntervalSet::whole()
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<Bound> IntervalSet<Bound>
+where
+    Bound: Width + Num + NumCast,
+    <Bound as Width>::Output: ToPrimitive + NumCast,
+{
+    /// Draws `k` distinct values of the set uniformly at random, or all of them if the set has at most `k` values.
+    /// The values are returned in increasing order.
+    /// Only the `k` drawn positions are stored (Floyd's sampling algorithm), so this is efficient even for huge sets.
+    /// ```
+    /// # use interval::prelude::*;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let interval_set = [(0, 999), (5000, 5999)].to_interval_set();
+    /// let sample = interval_set.sample_without_replacement(10, &mut rng);
+    /// assert_eq!(sample.len(), 10);
+    /// assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    /// assert!(sample.iter().all(|v| interval_set.contains(v)));
+    /// ```
+    pub fn sample_without_replacement<R>(&self, k: usize, rng: &mut R) -> Vec<Bound>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        // The size of a set always fits in 64 bits since the widest bounds are 64-bit integers.
+        let size = self.size.to_u64().unwrap();
+        let k = min(k as u64, size);
+        let mut positions = BTreeSet::new();
+        for j in size - k..size {
+            let position = rng.gen_range(0..=j);
+            if !positions.insert(position) {
+                positions.insert(j);
+            }
+        }
+        let mut res = Vec::with_capacity(positions.len());
+        let mut intervals = self.intervals.iter();
+        // `start` is the position of the lower bound of `interval` among the values of the set.
+        let mut interval = intervals.next();
+        let mut start = 0;
+        for position in positions {
+            let mut i = interval.unwrap();
+            while position - start >= i.size().to_u64().unwrap() {
+                start += i.size().to_u64().unwrap();
+                interval = intervals.next();
+                i = interval.unwrap();
+            }
+            let offset = <<Bound as Width>::Output as NumCast>::from(position - start).unwrap();
+            res.push(add_width(i.lower(), offset));
+        }
+        res
+    }
+}
+
+#[allow(non_upper_case_globals)]
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    use super::*;
+
+    const extend_example: [(i32, i32); 2] = [(11, 33), (-55, -44)];
+
+    fn test_inside_outside(is: IntervalSet<i32>, inside: Vec<i32>, outside: Vec<i32>) {
+        for i in &inside {
+            assert!(
+                is.contains(i),
+                "{} is not contained inside {}, but it should.",
+                i,
+                is
+            );
+        }
+        for i in &outside {
+            assert!(
                 !is.contains(i),
                 "{} is contained inside {}, but it should not.",
                 i,
@@ -1460,443 +4613,3277 @@ mod tests {
         }
     }
 
-    // precondition: `intervals` must be a valid intern representation of the interval set.
-    fn make_interval_set(intervals: Vec<(i32, i32)>) -> IntervalSet<i32> {
-        intervals.to_interval_set()
+    // precondition: `intervals` must be a valid intern representation of the interval set.
+    fn make_interval_set(intervals: Vec<(i32, i32)>) -> IntervalSet<i32> {
+        intervals.to_interval_set()
+    }
+
+    fn test_result(test_id: String, result: &IntervalSet<i32>, expected: &IntervalSet<i32>) {
+        assert!(
+            result.intervals == expected.intervals,
+            "{} | {} is different from the expected value: {}.",
+            test_id,
+            result,
+            expected
+        );
+    }
+
+    fn test_binary_op_sym<F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: Vec<(i32, i32)>,
+        op: F,
+        expected: Vec<(i32, i32)>,
+    ) where
+        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> IntervalSet<i32>,
+    {
+        test_binary_op(
+            test_id.clone(),
+            a.clone(),
+            b.clone(),
+            |i, j| op(i, j),
+            expected.clone(),
+        );
+        test_binary_op(test_id, b, a, op, expected);
+    }
+
+    fn test_binary_op<F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: Vec<(i32, i32)>,
+        op: F,
+        expected: Vec<(i32, i32)>,
+    ) where
+        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> IntervalSet<i32>,
+    {
+        println!("Info: {}.", test_id);
+        let a = make_interval_set(a);
+        let b = make_interval_set(b);
+        let expected = make_interval_set(expected);
+        test_result(test_id, &op(&a, &b), &expected);
+    }
+
+    fn test_binary_value_op<F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: i32,
+        op: F,
+        expected: Vec<(i32, i32)>,
+    ) where
+        F: Fn(&IntervalSet<i32>, i32) -> IntervalSet<i32>,
+    {
+        println!("Info: {}.", test_id);
+        let a = make_interval_set(a);
+        let expected = make_interval_set(expected);
+        test_result(test_id, &op(&a, b), &expected);
+    }
+
+    fn test_binary_bool_op_sym<F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: Vec<(i32, i32)>,
+        op: F,
+        expected: bool,
+    ) where
+        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> bool,
+    {
+        test_binary_bool_op(
+            test_id.clone(),
+            a.clone(),
+            b.clone(),
+            |i, j| op(i, j),
+            expected,
+        );
+        test_binary_bool_op(test_id, b, a, op, expected);
+    }
+
+    fn test_binary_bool_op<F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: Vec<(i32, i32)>,
+        op: F,
+        expected: bool,
+    ) where
+        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> bool,
+    {
+        println!("Info: {}.", test_id);
+        let a = make_interval_set(a);
+        let b = make_interval_set(b);
+        assert_eq!(op(&a, &b), expected);
+    }
+
+    fn test_binary_value_bool_op<V, F>(
+        test_id: String,
+        a: Vec<(i32, i32)>,
+        b: V,
+        op: F,
+        expected: bool,
+    ) where
+        F: Fn(&IntervalSet<i32>, &V) -> bool,
+    {
+        println!("Info: {}.", test_id);
+        let a = make_interval_set(a);
+        assert_eq!(op(&a, &b), expected);
+    }
+
+    fn test_op<F>(test_id: String, a: Vec<(i32, i32)>, op: F, expected: Vec<(i32, i32)>)
+    where
+        F: Fn(&IntervalSet<i32>) -> IntervalSet<i32>,
+    {
+        println!("Info: {}.", test_id);
+        let a = make_interval_set(a);
+        let expected = make_interval_set(expected);
+        let result = op(&a);
+        test_result(test_id, &result, &expected);
+    }
+
+    #[test]
+    fn test_contains() {
+        let cases = vec![
+            (vec![], vec![], vec![-2, -1, 0, 1, 2]),
+            (vec![(1, 2)], vec![1, 2], vec![-1, 0, 3, 4]),
+            (
+                vec![(1, 2), (7, 9)],
+                vec![1, 2, 7, 8, 9],
+                vec![-1, 0, 3, 4, 5, 6, 10, 11],
+            ),
+            (
+                vec![(1, 2), (4, 5), (7, 9)],
+                vec![1, 2, 4, 5, 7, 8, 9],
+                vec![-1, 0, 3, 6, 10, 11],
+            ),
+        ];
+
+        for (is, inside, outside) in cases {
+            let is = make_interval_set(is);
+            test_inside_outside(is, inside, outside);
+        }
+    }
+
+    #[test]
+    fn test_for_all_pairs_skips_empty() {
+        let a = make_interval_set(vec![(0, 1), (5, 6)]);
+        let b = make_interval_set(vec![(10, 10), (20, 21)]);
+        // Pairs involving `(5, 6)` produce an empty interval.
+        let res = a.for_all_pairs(&b, |i, j| {
+            if i.lower() == 5 {
+                Interval::empty()
+            } else {
+                i + j
+            }
+        });
+        assert_eq!(res, make_interval_set(vec![(10, 11), (20, 22)]));
+        assert_eq!(res.size(), 5);
+        assert!(res.validate().is_ok());
+        let none = a.for_all_pairs(&b, |_, _| Interval::empty());
+        assert!(none.is_empty());
+        assert!(none.validate().is_ok());
+        assert!(IntervalSet::from_interval(Interval::<i32>::empty()).is_empty());
+    }
+
+    #[test]
+    fn test_extremes_no_overflow() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            (1, vec![(min, min)], vec![(min + 1, max)]),
+            (2, vec![(max, max)], vec![(min, max - 1)]),
+            (3, vec![(min, min), (max, max)], vec![(min + 1, max - 1)]),
+            (
+                4,
+                vec![(min, min), (min + 2, max - 2), (max, max)],
+                vec![(min + 1, min + 1), (max - 1, max - 1)],
+            ),
+            (5, vec![(min, max)], vec![]),
+        ];
+        for (id, a, expected) in cases {
+            test_op(
+                format!("test #{} of complement at extremes", id),
+                a.clone(),
+                |x| x.complement(),
+                expected,
+            );
+            let a = make_interval_set(a);
+            assert_eq!(a.complement().complement(), a);
+            assert_eq!(a.shrink_left(min), a);
+            assert_eq!(a.shrink_right(max), a);
+            assert_eq!(
+                a.shrink_left(max),
+                a.intersection(&IntervalSet::singleton(max))
+            );
+            assert_eq!(
+                a.shrink_right(min),
+                a.intersection(&IntervalSet::singleton(min))
+            );
+        }
+    }
+
+    #[test]
+    fn test_complement_saturates_on_corrupted_sets() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        // Sets breaking the invariants, with the bound next to an interval out of the type.
+        // Their size could overflow, any positive size marks them as non-empty.
+        let broken = |intervals: Vec<(i32, i32)>| IntervalSet {
+            size: 1,
+            intervals: intervals.into_iter().map(|i| i.to_interval()).collect(),
+        };
+        let cases = vec![
+            (
+                1,
+                vec![(0, i32::MAX), (i32::MAX, i32::MAX)],
+                vec![(min, -1)],
+            ),
+            (2, vec![(min, min), (min, 0)], vec![(1, max)]),
+            (3, vec![(min, 5), (5, max)], vec![]),
+            (4, vec![(min, 5), (6, 8), (12, max)], vec![(9, 11)]),
+        ];
+        for (id, a, expected) in cases {
+            test_result(
+                format!("test #{} of complement of a corrupted set", id),
+                &broken(a).complement(),
+                &make_interval_set(expected),
+            );
+        }
+    }
+
+    #[test]
+    fn test_complement_representable_extremes() {
+        let max = <u8 as Width>::max_value();
+        assert_eq!(max, 254);
+        let cases = vec![
+            (vec![], vec![(0, max)]),
+            (vec![(max, max)], vec![(0, max - 1)]),
+            (vec![(0, 0)], vec![(1, max)]),
+            (vec![(0, 0), (max, max)], vec![(1, max - 1)]),
+        ];
+        for (a, expected) in cases {
+            let a: IntervalSet<u8> = a.to_interval_set();
+            let expected: IntervalSet<u8> = expected.to_interval_set();
+            assert_eq!(a.complement(), expected);
+            assert_eq!(a.complement().complement(), a);
+            assert_eq!(a.complement().size() + a.size(), max + 1);
+        }
+    }
+
+    #[test]
+    fn test_complement() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+
+        let cases = vec![
+            (1, vec![], vec![(min, max)]),
+            (2, vec![(min, max)], vec![]),
+            (3, vec![(0, 0)], vec![(min, -1), (1, max)]),
+            (4, vec![(-5, 5)], vec![(min, -6), (6, max)]),
+            (5, vec![(-5, -1), (1, 5)], vec![(min, -6), (0, 0), (6, max)]),
+            (6, vec![(min, -1), (1, 5)], vec![(0, 0), (6, max)]),
+            (7, vec![(-5, -1), (1, max)], vec![(min, -6), (0, 0)]),
+            (8, vec![(min, -1), (1, max)], vec![(0, 0)]),
+            (
+                9,
+                vec![(-5, -3), (0, 1), (3, 5)],
+                vec![(min, -6), (-2, -1), (2, 2), (6, max)],
+            ),
+        ];
+
+        for (id, a, expected) in cases {
+            test_op(
+                format!("test #{} of complement", id),
+                a.clone(),
+                |x| x.complement(),
+                expected,
+            );
+            test_op(
+                format!("test #{} of complement(complement)", id),
+                a.clone(),
+                |x| x.complement().complement(),
+                a,
+            );
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        // Note: the first number is the test id, so it should be easy to identify which test has failed.
+        // The two first vectors are the operands and the expected result is last.
+        let sym_cases = vec![
+            // identity tests
+            (1, vec![], vec![], vec![]),
+            (2, vec![], vec![(1, 2)], vec![(1, 2)]),
+            (3, vec![], vec![(1, 2), (7, 9)], vec![(1, 2), (7, 9)]),
+            (4, vec![(1, 2), (7, 9)], vec![(1, 2)], vec![(1, 2), (7, 9)]),
+            (
+                5,
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+            ),
+            // front tests
+            (
+                6,
+                vec![(-3, -1)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, -1), (1, 2), (7, 9)],
+            ),
+            (
+                7,
+                vec![(-3, 0)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 2), (7, 9)],
+            ),
+            (
+                8,
+                vec![(-3, 1)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 2), (7, 9)],
+            ),
+            // middle tests
+            (9, vec![(2, 7)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            (10, vec![(3, 7)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            (
+                11,
+                vec![(4, 5)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (4, 5), (7, 9)],
+            ),
+            (12, vec![(2, 8)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            (13, vec![(2, 6)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            (14, vec![(3, 6)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            // back tests
+            (15, vec![(8, 9)], vec![(1, 2), (7, 9)], vec![(1, 2), (7, 9)]),
+            (
+                16,
+                vec![(8, 10)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 10)],
+            ),
+            (
+                17,
+                vec![(9, 10)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 10)],
+            ),
+            (
+                18,
+                vec![(6, 10)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (6, 10)],
+            ),
+            (
+                19,
+                vec![(10, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 11)],
+            ),
+            (
+                20,
+                vec![(11, 12)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9), (11, 12)],
+            ),
+            // mixed tests
+            (
+                21,
+                vec![(-3, -1), (4, 5), (11, 12)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, -1), (1, 2), (4, 5), (7, 9), (11, 12)],
+            ),
+            (
+                22,
+                vec![(-3, 0), (3, 6), (10, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 11)],
+            ),
+            (
+                23,
+                vec![(-3, 1), (3, 7), (9, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 11)],
+            ),
+            (
+                24,
+                vec![(-3, 5), (7, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 5), (7, 11)],
+            ),
+            (
+                25,
+                vec![(-3, 5), (7, 8), (12, 12)],
+                vec![(1, 2), (7, 9)],
+                vec![(-3, 5), (7, 9), (12, 12)],
+            ),
+            // englobing tests
+            (26, vec![(-1, 11)], vec![(1, 2), (7, 9)], vec![(-1, 11)]),
+        ];
+
+        for (id, a, b, expected) in sym_cases {
+            test_binary_op_sym(
+                format!("test #{} of union", id),
+                a,
+                b,
+                |x, y| x.union(y),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn test_intersection() {
+        // Note: the first number is the test id, so it should be easy to identify which test has failed.
+        // The two first vectors are the operands and the expected result is last.
+        let sym_cases = vec![
+            // identity tests
+            (1, vec![], vec![], vec![]),
+            (2, vec![], vec![(1, 2)], vec![]),
+            (3, vec![], vec![(1, 2), (7, 9)], vec![]),
+            (4, vec![(1, 2), (7, 9)], vec![(1, 2)], vec![(1, 2)]),
+            (
+                5,
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+            ),
+            // front tests
+            (6, vec![(-3, -1)], vec![(1, 2), (7, 9)], vec![]),
+            (7, vec![(-3, 0)], vec![(1, 2), (7, 9)], vec![]),
+            (8, vec![(-3, 1)], vec![(1, 2), (7, 9)], vec![(1, 1)]),
+            // middle tests
+            (9, vec![(2, 7)], vec![(1, 2), (7, 9)], vec![(2, 2), (7, 7)]),
+            (10, vec![(3, 7)], vec![(1, 2), (7, 9)], vec![(7, 7)]),
+            (11, vec![(4, 5)], vec![(1, 2), (7, 9)], vec![]),
+            (12, vec![(2, 8)], vec![(1, 2), (7, 9)], vec![(2, 2), (7, 8)]),
+            (13, vec![(2, 6)], vec![(1, 2), (7, 9)], vec![(2, 2)]),
+            (14, vec![(3, 6)], vec![(1, 2), (7, 9)], vec![]),
+            // back tests
+            (15, vec![(8, 9)], vec![(1, 2), (7, 9)], vec![(8, 9)]),
+            (16, vec![(8, 10)], vec![(1, 2), (7, 9)], vec![(8, 9)]),
+            (17, vec![(9, 10)], vec![(1, 2), (7, 9)], vec![(9, 9)]),
+            (18, vec![(6, 10)], vec![(1, 2), (7, 9)], vec![(7, 9)]),
+            (19, vec![(10, 11)], vec![(1, 2), (7, 9)], vec![]),
+            (20, vec![(11, 12)], vec![(1, 2), (7, 9)], vec![]),
+            // mixed tests
+            (
+                21,
+                vec![(-3, -1), (4, 5), (11, 12)],
+                vec![(1, 2), (7, 9)],
+                vec![],
+            ),
+            (
+                22,
+                vec![(-3, 0), (3, 6), (10, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![],
+            ),
+            (
+                23,
+                vec![(-3, 1), (3, 7), (9, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 1), (7, 7), (9, 9)],
+            ),
+            (
+                24,
+                vec![(-3, 5), (7, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+            ),
+            (
+                25,
+                vec![(-3, 5), (7, 8), (12, 12)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 8)],
+            ),
+            // englobing tests
+            (
+                26,
+                vec![(-1, 11)],
+                vec![(1, 2), (7, 9)],
+                vec![(1, 2), (7, 9)],
+            ),
+        ];
+
+        for (id, a, b, expected) in sym_cases {
+            test_binary_op_sym(
+                format!("test #{} of intersection", id),
+                a,
+                b,
+                |x, y| x.intersection(y),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn test_union_intersection_capacity() {
+        let n = 10_000;
+        let a = (0..n)
+            .map(|i| (5 * i, 5 * i + 1))
+            .collect::<Vec<_>>()
+            .to_interval_set();
+        let b = (0..n)
+            .map(|i| (5 * i + 1, 5 * i + 2))
+            .collect::<Vec<_>>()
+            .to_interval_set();
+        let c = (0..n)
+            .map(|i| (5 * i + 3, 5 * i + 3))
+            .collect::<Vec<_>>()
+            .to_interval_set();
+
+        let union = a.union(&c);
+        assert_eq!(union.interval_count(), 2 * n as usize);
+        assert!(union.intervals.capacity() <= 2 * n as usize);
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection.interval_count(), n as usize);
+        assert!(intersection.intervals.capacity() < 2 * n as usize);
+    }
+
+    #[test]
+    fn test_overlaps_within() {
+        let a = make_interval_set(vec![(1, 3), (20, 25), (50, 50)]);
+        let cases = vec![
+            (1, vec![], 100, false),
+            (2, vec![(2, 2)], 0, true),
+            (3, vec![(4, 5)], 0, true),
+            (4, vec![(5, 6)], 0, false),
+            (5, vec![(6, 8)], 1, false),
+            (6, vec![(6, 8)], 2, true),
+            (7, vec![(-3, -2), (10, 12)], 1, false),
+            (8, vec![(-3, -2), (10, 12)], 2, true),
+            (9, vec![(30, 40), (60, 70)], 3, false),
+            (10, vec![(30, 40), (60, 70)], 4, true),
+            (11, vec![(5, 16), (28, 45), (55, 100)], 1, true),
+            (12, vec![(5, 16), (28, 45), (55, 100)], 0, false),
+        ];
+        for (id, b, window, expected) in cases {
+            let b = make_interval_set(b);
+            assert_eq!(
+                a.overlaps_within(&b, window),
+                expected,
+                "test #{} of overlaps_within",
+                id
+            );
+            assert_eq!(
+                b.overlaps_within(&a, window),
+                expected,
+                "test #{} of overlaps_within",
+                id
+            );
+        }
+
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let far_cases = vec![
+            (
+                1,
+                (-2_000_000_000, -2_000_000_000),
+                (2_000_000_000, 2_000_000_000),
+                5,
+                false,
+            ),
+            (2, (min, min), (max, max), max, false),
+            (3, (min, min), (0, 0), max - 2, false),
+            (4, (min, min), (0, 0), max - 1, true),
+            (5, (min, -1), (max, max), max - 1, false),
+            (6, (min, -1), (max, max), max, true),
+        ];
+        for (id, a, b, window, expected) in far_cases {
+            let (a, b) = (make_interval_set(vec![a]), make_interval_set(vec![b]));
+            assert_eq!(
+                a.overlaps_within(&b, window),
+                expected,
+                "test #{} of overlaps_within at extremes",
+                id
+            );
+            assert_eq!(
+                b.overlaps_within(&a, window),
+                expected,
+                "test #{} of overlaps_within at extremes",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_disjoint_interval() {
+        let sets = vec![
+            vec![],
+            vec![(1, 3), (7, 8)],
+            vec![(-5, -5), (0, 10), (20, 30)],
+        ];
+        let intervals = vec![
+            (4, 6),
+            (3, 6),
+            (4, 7),
+            (-10, 0),
+            (-10, 40),
+            (9, 19),
+            (11, 19),
+            (31, 40),
+            (5, 4),
+        ];
+        for a in sets {
+            let a = make_interval_set(a);
+            for &(l, u) in &intervals {
+                let i = Interval::new(l, u);
+                let expected = if i.is_empty() {
+                    true
+                } else {
+                    a.is_disjoint(&IntervalSet::new(l, u))
+                };
+                assert_eq!(a.is_disjoint_interval(&i), expected, "{} and {}", a, i);
+            }
+        }
+        let interval_set = make_interval_set(vec![(1, 3), (7, 8)]);
+        assert!(interval_set.is_disjoint_interval(&Interval::new(4, 6)));
+        assert!(!interval_set.is_disjoint_interval(&Interval::new(4, 7)));
+        assert!(!interval_set.is_disjoint_interval(&Interval::new(3, 3)));
+    }
+
+    #[test]
+    fn test_checked_mul() {
+        let cases = vec![
+            (1, vec![], vec![(1, 5)]),
+            (2, vec![(1, 2), (5, 6)], vec![(0, 0), (3, 4)]),
+            (3, vec![(-3, 2)], vec![(-4, -1), (7, 9)]),
+            (4, vec![(-46340, 46340)], vec![(-46340, 46340)]),
+        ];
+        for (id, a, b) in cases {
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            assert_eq!(
+                a.checked_mul(&b),
+                Some(&a * &b),
+                "test #{} of checked_mul",
+                id
+            );
+            assert_eq!(
+                b.checked_mul(&a),
+                Some(&a * &b),
+                "test #{} of checked_mul",
+                id
+            );
+        }
+        let max = i32::MAX;
+        let cases = vec![
+            (1, vec![(0, max)], vec![(2, 2)]),
+            (2, vec![(1, 3), (max / 2, max / 2 + 1)], vec![(2, 2)]),
+            (3, vec![(-46341, 0)], vec![(0, 46341)]),
+            (4, vec![(-2, -1)], vec![(max / 2 + 1, max / 2 + 1)]),
+        ];
+        for (id, a, b) in cases {
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            assert_eq!(a.checked_mul(&b), None, "test #{} of checked_mul", id);
+            assert_eq!(b.checked_mul(&a), None, "test #{} of checked_mul", id);
+        }
+        let interval_set = make_interval_set(vec![(-5, 1), (100, 200)]);
+        assert_eq!(interval_set.checked_mul_bound(&3), Some(&interval_set * 3));
+        assert_eq!(
+            interval_set.checked_mul_bound(&0),
+            Some(IntervalSet::singleton(0))
+        );
+        assert_eq!(interval_set.checked_mul_bound(&(max / 100)), None);
+        assert_eq!(
+            IntervalSet::<i32>::empty().checked_mul_bound(&max),
+            Some(IntervalSet::empty())
+        );
+        let bytes = IntervalSet::new(0u8, 127);
+        assert_eq!(bytes.checked_mul_bound(&2), Some(IntervalSet::new(0, 254)));
+        assert_eq!(IntervalSet::new(0u8, 85).checked_mul_bound(&3), None);
+    }
+
+    #[test]
+    fn test_mul_exact_if_possible() {
+        let cases = vec![
+            (1, vec![], vec![(1, 5)], true),
+            (2, vec![(3, 3)], vec![(4, 4), (7, 7)], true),
+            (3, vec![(1, 2)], vec![(1, 2)], false),
+            (4, vec![(0, 3)], vec![(0, 2)], false),
+            (5, vec![(2, 2)], vec![(1, 3)], false),
+            (6, vec![(1, 1)], vec![(10, 20), (30, 40)], true),
+            (7, vec![(0, 0)], vec![(10, 20)], true),
+            (8, vec![(0, 1)], vec![(-5, 7)], true),
+            (9, vec![(0, 1)], vec![(3, 5)], false),
+            (10, vec![(-1, 0)], vec![(1, 5)], true),
+            (11, vec![(-1, 0)], vec![(2, 5)], false),
+            (12, vec![(-1, 1)], vec![(-2, 3)], true),
+            (13, vec![(-1, 1)], vec![(2, 3)], false),
+            (14, vec![(-3, -2)], vec![(-1, 1)], false),
+            (15, vec![(-1, -1)], vec![(-8, -3)], true),
+        ];
+        for (id, a, b, exact) in cases {
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            let product = &a * &b;
+            assert_eq!(
+                a.mul_exact_if_possible(&b),
+                (product.clone(), exact),
+                "test #{} of mul_exact_if_possible",
+                id
+            );
+            assert_eq!(
+                b.mul_exact_if_possible(&a),
+                (product.clone(), exact),
+                "test #{} of mul_exact_if_possible",
+                id
+            );
+            if exact {
+                let products: Vec<i32> = a
+                    .iter_values()
+                    .flat_map(|x| b.iter_values().map(move |y| x * y))
+                    .collect();
+                assert_eq!(
+                    IntervalSet::from(&products[..]),
+                    product,
+                    "test #{} of mul_exact_if_possible",
+                    id
+                );
+            }
+        }
+        let max = <u8 as Width>::max_value();
+        let a = IntervalSet::new(0u8, 1);
+        assert!(a.mul_exact_if_possible(&IntervalSet::new(0, max)).1);
+    }
+
+    #[test]
+    fn test_coverage_union() {
+        let cases = vec![
+            (1, vec![], vec![], vec![]),
+            (2, vec![(0, 5)], vec![], vec![((0, 5), 1)]),
+            (
+                3,
+                vec![(0, 5)],
+                vec![(3, 8)],
+                vec![((0, 2), 1), ((3, 5), 2), ((6, 8), 1)],
+            ),
+            (4, vec![(0, 5)], vec![(0, 5)], vec![((0, 5), 2)]),
+            (5, vec![(0, 2)], vec![(3, 5)], vec![((0, 5), 1)]),
+            (
+                6,
+                vec![(0, 10), (20, 25)],
+                vec![(2, 3), (5, 5), (10, 12), (30, 30)],
+                vec![
+                    ((0, 1), 1),
+                    ((2, 3), 2),
+                    ((4, 4), 1),
+                    ((5, 5), 2),
+                    ((6, 9), 1),
+                    ((10, 10), 2),
+                    ((11, 12), 1),
+                    ((20, 25), 1),
+                    ((30, 30), 1),
+                ],
+            ),
+            (
+                7,
+                vec![(0, 3), (6, 9)],
+                vec![(3, 6)],
+                vec![
+                    ((0, 2), 1),
+                    ((3, 3), 2),
+                    ((4, 5), 1),
+                    ((6, 6), 2),
+                    ((7, 9), 1),
+                ],
+            ),
+            (
+                8,
+                vec![(0, 20), (25, 35)],
+                vec![(5, 5), (7, 8), (30, 40)],
+                vec![
+                    ((0, 4), 1),
+                    ((5, 5), 2),
+                    ((6, 6), 1),
+                    ((7, 8), 2),
+                    ((9, 20), 1),
+                    ((25, 29), 1),
+                    ((30, 35), 2),
+                    ((36, 40), 1),
+                ],
+            ),
+        ];
+        for (id, a, b, expected) in cases {
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            let expected: Vec<_> = expected
+                .into_iter()
+                .map(|((l, u), n)| (Interval::new(l, u), n))
+                .collect();
+            assert_eq!(
+                a.coverage_union(&b),
+                expected,
+                "test #{} of coverage_union",
+                id
+            );
+            assert_eq!(
+                b.coverage_union(&a),
+                expected,
+                "test #{} of coverage_union",
+                id
+            );
+        }
+        let max = <i32 as Width>::max_value();
+        let a = IntervalSet::new(0, max);
+        let b = IntervalSet::new(10, max);
+        assert_eq!(
+            a.coverage_union(&b),
+            vec![(Interval::new(0, 9), 1), (Interval::new(10, max), 2)]
+        );
+    }
+
+    #[test]
+    fn test_set_op_sizes() {
+        let sets = vec![
+            vec![],
+            vec![(0, 10)],
+            vec![(1, 3), (8, 8), (10, 11)],
+            vec![(2, 5), (7, 8), (12, 15)],
+            vec![(-5, 2), (5, 7), (11, 20)],
+            vec![(-20, -10), (30, 40)],
+        ];
+        for a in &sets {
+            for b in &sets {
+                let a = make_interval_set(a.clone());
+                let b = make_interval_set(b.clone());
+                assert_eq!(a.intersection_size(&b), a.intersection(&b).size());
+                assert_eq!(a.union_size(&b), a.union(&b).size());
+                assert_eq!(
+                    a.symmetric_difference_size(&b),
+                    a.symmetric_difference(&b).size()
+                );
+            }
+        }
+        let whole = IntervalSet::<u8>::whole();
+        assert_eq!(whole.union_size(&whole), 255);
+        assert_eq!(whole.symmetric_difference_size(&IntervalSet::empty()), 255);
+    }
+
+    #[test]
+    fn test_intersect_interval() {
+        let sets = vec![
+            vec![],
+            vec![(1, 2)],
+            vec![(1, 2), (7, 9)],
+            vec![(-3, -1), (4, 5), (11, 12)],
+            vec![(-3, 0), (3, 6), (10, 11)],
+        ];
+        let intervals = vec![
+            Interval::empty(),
+            Interval::new(-5, -4),
+            Interval::new(-3, 1),
+            Interval::new(2, 7),
+            Interval::new(5, 10),
+            Interval::new(-10, 20),
+            Interval::singleton(11),
+            Interval::new(13, 20),
+        ];
+
+        for set in sets {
+            let set = make_interval_set(set);
+            for i in &intervals {
+                let expected = if i.is_empty() {
+                    IntervalSet::empty()
+                } else {
+                    set.intersection(&IntervalSet::from_interval(*i))
+                };
+                test_result(
+                    format!("intersect_interval of {} and {}", set, i),
+                    &set.intersect_interval(i),
+                    &expected,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_interval_set() {
+        // This example should not panic, and should yield the correct result.
+        let intervals = vec![(3, 8), (2, 5)].to_interval_set();
+        assert_eq!(intervals.interval_count(), 1);
+        assert_eq!(intervals.lower(), 2);
+        assert_eq!(intervals.upper(), 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "This operation is only for pushing interval to the back of the array, possibly overlapping with the last element."
+    )]
+    fn test_extend_back() {
+        // Calling extend_at_back with unordered input should panic.
+        let mut set = IntervalSet::empty();
+        let intervals = extend_example.map(|i| i.to_interval());
+        set.extend_at_back(intervals);
+        assert_eq!(set.interval_count(), 2);
+    }
+
+    #[test]
+    fn test_extend_empty() {
+        // Calling extend with unordered input should not panic.
+        let mut set = IntervalSet::empty();
+        let intervals = extend_example.map(|i| i.to_interval());
+        set.extend(intervals);
+        assert_eq!(set.interval_count(), 2);
+    }
+
+    #[test]
+    fn test_extend_non_empty() {
+        // Extending an IntervalSet with intervals that belong at the start or
+        // the middle of the set should not panic.
+        let mut intervals = vec![(10, 15), (20, 30)].to_interval_set();
+        let at_start = vec![(0, 5).to_interval()];
+        intervals.extend(at_start);
+        let in_middle = vec![(17, 18).to_interval()];
+        intervals.extend(in_middle);
+
+        assert_eq!(intervals.interval_count(), 4);
+        assert_eq!(intervals.lower(), 0);
+        assert_eq!(intervals.upper(), 30);
+    }
+
+    #[test]
+    fn test_extend_one_at_a_time() {
+        // Appending at the back must not rebuild the whole set at each call.
+        let n = 10_000;
+        let mut intervals = IntervalSet::empty();
+        UNION_CALLS.with(|calls| calls.set(0));
+        for i in 0..n {
+            intervals.extend([Interval::singleton(3 * i)]);
+        }
+        assert_eq!(UNION_CALLS.with(|calls| calls.get()), 0);
+        assert_eq!(intervals.interval_count(), n as usize);
+        assert_eq!(intervals.size(), n as u32);
+        assert_eq!(intervals.upper(), 3 * (n - 1));
+        // Adjacent intervals appended at the back are still merged.
+        intervals.extend([Interval::new(3 * n - 2, 3 * n)]);
+        assert_eq!(UNION_CALLS.with(|calls| calls.get()), 0);
+        assert_eq!(intervals.interval_count(), n as usize);
+        assert_eq!(intervals.upper(), 3 * n);
+    }
+
+    #[test]
+    fn test_extend_batches_at_back() {
+        let mut intervals = IntervalSet::empty();
+        let mut expected = IntervalSet::empty();
+        UNION_CALLS.with(|calls| calls.set(0));
+        for batch in 0..100 {
+            let start = 20 * batch;
+            // An unsorted batch landing after the last interval of the set.
+            let new = [
+                Interval::new(start + 10, start + 12),
+                Interval::new(start, start + 3),
+                Interval::singleton(start + 7),
+            ];
+            intervals.extend(new);
+            for i in &new {
+                expected = expected.union(&IntervalSet::from_interval(*i));
+            }
+        }
+        let union_calls = UNION_CALLS.with(|calls| calls.get());
+        assert_eq!(intervals, expected);
+        assert_eq!(intervals.validate(), Ok(()));
+        // Only the unions building `expected` were counted.
+        assert_eq!(union_calls, 300);
+
+        UNION_CALLS.with(|calls| calls.set(0));
+        intervals.extend([Interval::new(5, 6)]);
+        assert_eq!(UNION_CALLS.with(|calls| calls.get()), 1);
+        assert_eq!(intervals, expected.union(&IntervalSet::new(5, 6)));
+    }
+
+    #[test]
+    fn test_from_sorted_unchecked() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            vec![],
+            vec![(1, 2)],
+            vec![(-5, -3), (0, 1), (3, 5)],
+            vec![(min, -1), (1, max)],
+        ];
+
+        for intervals in cases {
+            let expected = make_interval_set(intervals.clone());
+            let intervals = intervals.into_iter().map(|i| i.to_interval()).collect();
+            let result = unsafe { IntervalSet::from_sorted_unchecked(intervals) };
+            assert_eq!(result.intervals, expected.intervals);
+            assert_eq!(result.size(), expected.size());
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`from_sorted_unchecked` expects ordered and non-joinable intervals."
+    )]
+    fn test_from_sorted_unchecked_unordered() {
+        let intervals = vec![Interval::new(5, 6), Interval::new(1, 2)];
+        let _ = unsafe { IntervalSet::from_sorted_unchecked(intervals) };
+    }
+
+    #[test]
+    #[should_panic(expected = "`from_sorted_unchecked` expects non-empty intervals.")]
+    fn test_from_sorted_unchecked_empty_interval() {
+        let intervals = vec![Interval::new(1, 2), Interval::empty()];
+        let _ = unsafe { IntervalSet::from_sorted_unchecked(intervals) };
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        let cases = vec![
+            (1, vec![], vec![]),
+            (2, vec![(1, 0)], vec![]),
+            (
+                3,
+                vec![(1, 3), (2, 5), (6, 6), (9, 9)],
+                vec![(1, 6), (9, 9)],
+            ),
+            (4, vec![(1, 10), (2, 3), (4, 12)], vec![(1, 12)]),
+            (
+                5,
+                vec![(-5, -3), (-5, -4), (0, 1), (3, 5)],
+                vec![(-5, -3), (0, 1), (3, 5)],
+            ),
+            (6, vec![(0, 2), (5, 4), (3, 3)], vec![(0, 3)]),
+        ];
+        for (id, intervals, expected) in cases {
+            let result =
+                IntervalSet::from_sorted_iter(intervals.into_iter().map(|i| i.to_interval()));
+            let expected = make_interval_set(expected);
+            test_result(
+                format!("test #{} of from_sorted_iter", id),
+                &result,
+                &expected,
+            );
+            assert_eq!(result.size(), expected.size());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`from_sorted_iter` expects intervals sorted by lower bound.")]
+    fn test_from_sorted_iter_unordered() {
+        let intervals = vec![Interval::new(5, 6), Interval::new(1, 2)];
+        let _ = IntervalSet::from_sorted_iter(intervals);
+    }
+
+    #[test]
+    fn test_trim_empty_intervals() {
+        let broken = |intervals: Vec<(i32, i32)>, size: u32| IntervalSet {
+            intervals: intervals.into_iter().map(|i| i.to_interval()).collect(),
+            size,
+        };
+        let cases = vec![
+            (1, broken(vec![(1, 0)], 0), vec![]),
+            (2, broken(vec![(1, 2), (5, 4)], 2), vec![(1, 2)]),
+            (3, broken(vec![(1, 2), (5, 4), (3, 6)], 6), vec![(1, 6)]),
+            (
+                4,
+                broken(vec![(7, 6), (9, 9), (1, 2)], 3),
+                vec![(1, 2), (9, 9)],
+            ),
+            (5, broken(vec![(1, 5)], 5), vec![(1, 5)]),
+        ];
+        for (id, mut interval_set, expected) in cases {
+            interval_set.trim_empty_intervals();
+            assert_eq!(
+                interval_set.validate(),
+                Ok(()),
+                "test #{} of trim_empty_intervals",
+                id
+            );
+            assert_eq!(
+                interval_set,
+                make_interval_set(expected),
+                "test #{} of trim_empty_intervals",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_fold_intervals() {
+        let interval_set = make_interval_set(vec![(1, 2), (5, 6), (8, 20), (30, 40)]);
+        let threshold = 10;
+        let mut calls = 0;
+        let res = interval_set.try_fold_intervals(0, |total, i| {
+            calls += 1;
+            let total = total + i.size();
+            if total > threshold {
+                Err(total)
+            } else {
+                Ok(total)
+            }
+        });
+        assert_eq!(res, Err(17));
+        assert_eq!(calls, 3);
+
+        let res: Result<u32, u32> =
+            interval_set.try_fold_intervals(0, |total, i| Ok(total + i.size()));
+        assert_eq!(res, Ok(interval_set.size()));
+        let weighted: Result<i32, ()> =
+            interval_set.try_fold_intervals(0, |total, i| Ok(total + i.lower() * i.size() as i32));
+        assert_eq!(weighted, Ok(2 + 10 + 8 * 13 + 30 * 11));
+        let res: Result<u32, u32> =
+            IntervalSet::<i32>::empty().try_fold_intervals(7, |_, _| Err(0));
+        assert_eq!(res, Ok(7));
+    }
+
+    #[test]
+    fn test_validate() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let valid_cases = vec![
+            vec![],
+            vec![(1, 2)],
+            vec![(-5, -3), (0, 1), (3, 5)],
+            vec![(min, -1), (1, max)],
+        ];
+        for intervals in valid_cases {
+            assert_eq!(make_interval_set(intervals).validate(), Ok(()));
+        }
+
+        let broken = |intervals: Vec<(i32, i32)>, size: u32| IntervalSet {
+            intervals: intervals.into_iter().map(|i| i.to_interval()).collect(),
+            size,
+        };
+        let invalid_cases = vec![
+            (1, broken(vec![(1, 0)], 0), "Interval #0 is empty."),
+            (2, broken(vec![(1, 2), (5, 4)], 2), "Interval #1 is empty."),
+            (
+                3,
+                broken(vec![(5, 6), (1, 2)], 4),
+                "Intervals #0 and #1 are not sorted by lower bound.",
+            ),
+            (
+                4,
+                broken(vec![(1, 2), (1, 3)], 5),
+                "Intervals #0 and #1 are not sorted by lower bound.",
+            ),
+            (
+                5,
+                broken(vec![(-5, -3), (1, 2), (3, 5)], 8),
+                "Intervals #1 and #2 are joinable and should have been merged.",
+            ),
+            (
+                6,
+                broken(vec![(1, 4), (3, 5)], 7),
+                "Intervals #0 and #1 are joinable and should have been merged.",
+            ),
+            (
+                7,
+                broken(vec![(1, 2), (4, 5)], 5),
+                "The cached size differs from the sum of the sizes of the intervals.",
+            ),
+            (
+                8,
+                broken(vec![], 1),
+                "The cached size differs from the sum of the sizes of the intervals.",
+            ),
+        ];
+        for (id, interval_set, expected) in invalid_cases {
+            println!("Info: test #{} of validate.", id);
+            assert_eq!(interval_set.validate(), Err(String::from(expected)));
+        }
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let canonical_cases = vec![
+            vec![],
+            vec![(1, 2)],
+            vec![(-5, -3), (0, 1), (3, 5)],
+            vec![(min, -1), (1, max)],
+        ];
+        for intervals in canonical_cases {
+            let interval_set = make_interval_set(intervals);
+            assert!(interval_set.is_canonical(), "{:?}", interval_set);
+        }
+
+        let broken = |intervals: Vec<(i32, i32)>| IntervalSet {
+            intervals: intervals.into_iter().map(|i| i.to_interval()).collect(),
+            size: 0,
+        };
+        let non_canonical_cases = vec![
+            broken(vec![(1, 0)]),
+            broken(vec![(1, 2), (5, 4)]),
+            broken(vec![(5, 6), (1, 2)]),
+            broken(vec![(1, 2), (1, 3)]),
+            broken(vec![(-5, -3), (1, 2), (3, 5)]),
+            broken(vec![(1, 4), (3, 5)]),
+        ];
+        for interval_set in non_canonical_cases {
+            assert!(!interval_set.is_canonical(), "{:?}", interval_set);
+        }
+        // A wrong cached size does not break the order of the intervals.
+        assert!(broken(vec![(1, 2), (4, 5)]).is_canonical());
+    }
+
+    #[test]
+    fn test_bits() {
+        let cases = vec![
+            (1, 0, 0, vec![]),
+            (2, 0b1110_0011, 0, vec![(0, 1), (5, 7)]),
+            (3, 0b1110_0011, -3, vec![(-3, -2), (2, 4)]),
+            (4, 1 << 63, 0, vec![(63, 63)]),
+            (5, u64::MAX, 0, vec![(0, 63)]),
+            (6, u64::MAX - 2, 1, vec![(1, 1), (3, 64)]),
+        ];
+
+        for (id, mask, offset, expected) in cases {
+            println!("Info: test #{} of from_bits and to_bits.", id);
+            let expected = make_interval_set(expected);
+            let result = IntervalSet::from_bits(mask, offset);
+            assert_eq!(result.intervals, expected.intervals);
+            assert_eq!(result.size(), expected.size());
+            assert_eq!(result.to_bits(offset), Some(mask));
+        }
+
+        let out_of_range = vec![
+            (vec![(0, 1), (5, 7)], 1),
+            (vec![(0, 1), (5, 7)], -60),
+            (vec![(0, 64)], 0),
+            (vec![(-5, -1), (70, 80)], -5),
+        ];
+        for (intervals, offset) in out_of_range {
+            assert_eq!(make_interval_set(intervals).to_bits(offset), None);
+        }
+
+        let max = <u8 as Width>::max_value();
+        let interval_set = IntervalSet::<u8>::new(max - 1, max);
+        assert_eq!(interval_set.to_bits(max - 63), Some(0b11 << 62));
+        assert_eq!(
+            IntervalSet::<u8>::from_bits(0b11 << 62, max - 63),
+            interval_set
+        );
+        let min = <i8 as Width>::min_value();
+        assert_eq!(
+            IntervalSet::<i8>::from_bits(0b11, min),
+            IntervalSet::new(min, min + 1)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`from_bits` expects the values of the set bits to be representable by the bound."
+    )]
+    fn test_from_bits_overflow() {
+        let _ = IntervalSet::<i8>::from_bits(1 << 40, 100);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`from_bits` expects the values of the set bits to be representable by the bound."
+    )]
+    fn test_from_bits_above_width() {
+        let _ = IntervalSet::<u8>::from_bits(1 << 63, u8::MAX - 63);
+    }
+
+    #[test]
+    fn test_remove_interval() {
+        let cases = vec![
+            (1, vec![], (1, 2), vec![]),
+            (2, vec![(1, 9)], (4, 5), vec![(1, 3), (6, 9)]),
+            (3, vec![(1, 9)], (1, 5), vec![(6, 9)]),
+            (4, vec![(1, 9)], (5, 9), vec![(1, 4)]),
+            (5, vec![(1, 9)], (0, 10), vec![]),
+            (6, vec![(1, 2), (7, 9)], (3, 6), vec![(1, 2), (7, 9)]),
+            (7, vec![(1, 2), (7, 9)], (10, 12), vec![(1, 2), (7, 9)]),
+            (8, vec![(1, 2), (7, 9)], (-3, 0), vec![(1, 2), (7, 9)]),
+            (9, vec![(1, 2), (7, 9)], (2, 7), vec![(1, 1), (8, 9)]),
+            (
+                10,
+                vec![(-3, -1), (1, 2), (4, 5), (7, 9), (11, 12)],
+                (0, 8),
+                vec![(-3, -1), (9, 9), (11, 12)],
+            ),
+            (
+                11,
+                vec![(-3, -1), (1, 2), (4, 5), (7, 9), (11, 12)],
+                (-2, 11),
+                vec![(-3, -3), (12, 12)],
+            ),
+        ];
+
+        for (id, a, (lb, ub), expected) in cases {
+            let mut result = make_interval_set(a.clone());
+            result.remove_interval(&Interval::new(lb, ub));
+            let expected = make_interval_set(expected);
+            test_result(
+                format!("test #{} of remove_interval", id),
+                &result,
+                &expected,
+            );
+            assert_eq!(result.size(), expected.size());
+            let difference = make_interval_set(a).difference(&IntervalSet::new(lb, ub));
+            assert_eq!(result, difference);
+        }
+
+        let mut interval_set = vec![(1, 2), (7, 9)].to_interval_set();
+        interval_set.remove_interval(&Interval::empty());
+        assert_eq!(interval_set, vec![(1, 2), (7, 9)].to_interval_set());
+    }
+
+    #[test]
+    fn test_replace_interval() {
+        let a = vec![(1, 2), (6, 7), (12, 15)];
+        let cases = vec![
+            (1, vec![], 0, (1, 2), None, vec![]),
+            (2, a.clone(), 3, (0, 0), None, a.clone()),
+            (
+                3,
+                a.clone(),
+                1,
+                (6, 8),
+                Some((6, 7)),
+                vec![(1, 2), (6, 8), (12, 15)],
+            ),
+            (
+                4,
+                a.clone(),
+                1,
+                (3, 5),
+                Some((6, 7)),
+                vec![(1, 5), (12, 15)],
+            ),
+            (
+                5,
+                a.clone(),
+                1,
+                (8, 11),
+                Some((6, 7)),
+                vec![(1, 2), (8, 15)],
+            ),
+            (6, a.clone(), 1, (3, 11), Some((6, 7)), vec![(1, 15)]),
+            (
+                7,
+                a.clone(),
+                0,
+                (16, 21),
+                Some((1, 2)),
+                vec![(6, 7), (12, 21)],
+            ),
+            (
+                8,
+                a.clone(),
+                2,
+                (-5, -3),
+                Some((12, 15)),
+                vec![(-5, -3), (1, 2), (6, 7)],
+            ),
+            (9, a.clone(), 0, (0, 20), Some((1, 2)), vec![(0, 20)]),
+            (10, a, 1, (1, 0), Some((6, 7)), vec![(1, 2), (12, 15)]),
+        ];
+        for (id, a, index, (lb, ub), old, expected) in cases {
+            let mut result = make_interval_set(a);
+            let replaced = result.replace_interval(index, Interval::new(lb, ub));
+            assert_eq!(
+                replaced,
+                old.map(|(l, u)| Interval::new(l, u)),
+                "test #{} of replace_interval",
+                id
+            );
+            let expected = make_interval_set(expected);
+            test_result(
+                format!("test #{} of replace_interval", id),
+                &result,
+                &expected,
+            );
+            assert_eq!(result.size(), expected.size());
+            assert_eq!(result.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_grow_left_right() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            (1, vec![], 3, vec![], vec![]),
+            (
+                2,
+                vec![(5, 6), (10, 11)],
+                7,
+                vec![(-2, 6), (10, 11)],
+                vec![(5, 6), (10, 18)],
+            ),
+            (
+                3,
+                vec![(5, 6), (10, 11)],
+                0,
+                vec![(5, 6), (10, 11)],
+                vec![(5, 6), (10, 11)],
+            ),
+            (4, vec![(0, 0)], 2, vec![(-2, 0)], vec![(0, 2)]),
+            (
+                5,
+                vec![(min + 1, 0), (5, max - 1)],
+                3,
+                vec![(min, 0), (5, max - 1)],
+                vec![(min + 1, 0), (5, max)],
+            ),
+            (6, vec![(-1, 1)], max, vec![(min, 1)], vec![(-1, max)]),
+        ];
+        for (id, a, n, left, right) in cases {
+            test_op(
+                format!("test #{} of grow_left", id),
+                a.clone(),
+                |x| x.grow_left(n),
+                left,
+            );
+            test_op(
+                format!("test #{} of grow_right", id),
+                a,
+                |x| x.grow_right(n),
+                right,
+            );
+        }
+        let max = <u8 as Width>::max_value();
+        assert_eq!(
+            IntervalSet::new(3u8, 4).grow_left(u8::MAX),
+            IntervalSet::new(0, 4)
+        );
+        assert_eq!(
+            IntervalSet::new(3u8, 4).grow_right(u8::MAX),
+            IntervalSet::new(3, max)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grow_left_negative() {
+        IntervalSet::new(1, 2).grow_left(-1);
+    }
+
+    #[test]
+    fn test_grow_to_include() {
+        let cases = vec![
+            // empty and contained tests
+            (1, vec![], 5, vec![(5, 5)]),
+            (2, vec![(1, 2), (7, 9)], 1, vec![(1, 2), (7, 9)]),
+            (3, vec![(1, 2), (7, 9)], 8, vec![(1, 2), (7, 9)]),
+            // left and right tests
+            (4, vec![(1, 2), (7, 9)], -3, vec![(-3, 2), (7, 9)]),
+            (5, vec![(1, 2), (7, 9)], 0, vec![(0, 2), (7, 9)]),
+            (6, vec![(1, 2), (7, 9)], 10, vec![(1, 2), (7, 10)]),
+            (7, vec![(1, 2), (7, 9)], 15, vec![(1, 2), (7, 15)]),
+            // gap tests
+            (8, vec![(1, 2), (7, 9)], 3, vec![(1, 3), (7, 9)]),
+            (9, vec![(1, 2), (7, 9)], 6, vec![(1, 2), (6, 9)]),
+            (10, vec![(1, 2), (8, 9)], 5, vec![(1, 5), (8, 9)]),
+            (11, vec![(1, 2), (7, 9)], 4, vec![(1, 4), (7, 9)]),
+            (12, vec![(1, 2), (7, 9)], 5, vec![(1, 2), (5, 9)]),
+            // merging tests
+            (13, vec![(1, 2), (4, 9)], 3, vec![(1, 9)]),
+            (
+                14,
+                vec![(1, 2), (5, 6), (8, 9)],
+                3,
+                vec![(1, 3), (5, 6), (8, 9)],
+            ),
+            (15, vec![(1, 2), (4, 4), (6, 9)], 5, vec![(1, 2), (4, 9)]),
+            (16, vec![(1, 2), (4, 4), (6, 9)], 3, vec![(1, 4), (6, 9)]),
+        ];
+
+        for (id, a, value, expected) in cases {
+            println!("Info: test #{} of grow_to_include.", id);
+            let mut a = make_interval_set(a);
+            a.grow_to_include(value);
+            let expected = make_interval_set(expected);
+            test_result(format!("test #{} of grow_to_include", id), &a, &expected);
+            assert_eq!(a.size(), expected.size());
+        }
+    }
+
+    #[test]
+    fn test_structurally_eq() {
+        let a = make_interval_set(vec![(1, 3), (7, 9)]);
+        let mut corrupted = a.clone();
+        corrupted.size = 2;
+        assert!(a.structurally_eq(&corrupted));
+        assert!(corrupted.structurally_eq(&a));
+        assert_ne!(a, corrupted);
+        assert!(!a.structurally_eq(&make_interval_set(vec![(1, 3), (7, 8)])));
+        assert!(IntervalSet::<i32>::empty().structurally_eq(&IntervalSet::empty()));
+    }
+
+    #[test]
+    fn test_set_bounds() {
+        let mut intervals = vec![(0, 5), (10, 15), (20, 25)].to_interval_set();
+        intervals.set_bounds(|v| {
+            v[0] = Interval::new(12, 18);
+            v[2] = Interval::empty();
+            v.push(Interval::new(-5, -1));
+        });
+        assert_eq!(intervals, vec![(-5, -1), (10, 18)].to_interval_set());
+        assert_eq!(intervals.size(), 14u32);
+
+        intervals.set_bounds(|v| v.clear());
+        assert!(intervals.is_empty());
+        assert_eq!(intervals.size(), 0u32);
+    }
+
+    #[test]
+    fn test_to_interval_set_with_gap() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            (1, vec![], 2, vec![]),
+            (2, vec![(0, 2), (5, 7)], 2, vec![(0, 7)]),
+            (3, vec![(0, 2), (6, 7)], 2, vec![(0, 2), (6, 7)]),
+            (4, vec![(0, 2), (4, 4)], 0, vec![(0, 2), (4, 4)]),
+            (
+                5,
+                vec![(10, 12), (-3, -1), (2, 3), (20, 20)],
+                3,
+                vec![(-3, 3), (10, 12), (20, 20)],
+            ),
+            (6, vec![(0, 5), (2, 3), (7, 7)], 1, vec![(0, 7)]),
+            (
+                7,
+                vec![
+                    (-2_000_000_000, -2_000_000_000),
+                    (2_000_000_000, 2_000_000_000),
+                ],
+                5,
+                vec![
+                    (-2_000_000_000, -2_000_000_000),
+                    (2_000_000_000, 2_000_000_000),
+                ],
+            ),
+            (
+                8,
+                vec![(min, min), (max, max)],
+                max,
+                vec![(min, min), (max, max)],
+            ),
+            (9, vec![(min, min), (0, 0)], max, vec![(min, 0)]),
+            (
+                10,
+                vec![(min, min), (1, max)],
+                max - 1,
+                vec![(min, min), (1, max)],
+            ),
+            (11, vec![(min, min), (0, max)], max - 1, vec![(min, max)]),
+        ];
+        for (id, intervals, gap, expected) in cases {
+            assert_eq!(
+                intervals.to_interval_set_with_gap(gap),
+                make_interval_set(expected),
+                "test #{} of to_interval_set_with_gap",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_with() {
+        let within_3 = |a: &Interval<i32>, b: &Interval<i32>| b.lower() - a.upper() <= 3;
+        let cases = vec![
+            (1, vec![], vec![], vec![]),
+            (2, vec![(1, 2)], vec![(1, 2)], vec![(1, 2)]),
+            (
+                3,
+                vec![(10, 12), (1, 2), (5, 6), (20, 20)],
+                vec![(1, 2), (5, 6), (10, 12), (20, 20)],
+                vec![(1, 6), (10, 12), (20, 20)],
+            ),
+            (
+                4,
+                vec![(1, 2), (3, 4), (2, 8), (0, 0)],
+                vec![(0, 8)],
+                vec![(0, 8)],
+            ),
+            (
+                5,
+                vec![(1, 3), (1, 0), (7, 9)],
+                vec![(1, 3), (7, 9)],
+                vec![(1, 3), (7, 9)],
+            ),
+            (
+                6,
+                vec![(1, 3), (6, 7), (8, 8)],
+                vec![(1, 3), (6, 8)],
+                vec![(1, 8)],
+            ),
+        ];
+
+        for (id, intervals, never, expected) in cases {
+            let intervals: Vec<_> = intervals
+                .into_iter()
+                .map(|(l, u)| Interval::new(l, u))
+                .collect();
+            let default = IntervalSet::merge_with(intervals.clone(), |_, _| false);
+            assert_eq!(
+                default,
+                make_interval_set(never),
+                "test #{} of merge_with",
+                id
+            );
+            let merged = IntervalSet::merge_with(intervals, within_3);
+            assert_eq!(
+                merged,
+                make_interval_set(expected),
+                "test #{} of merge_with",
+                id
+            );
+            assert!(merged.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_build_from_sets() {
+        // Small overlapping sets, produced out of order.
+        let sets: Vec<IntervalSet<i32>> = (0..100)
+            .map(|k| {
+                let start = (k * 37) % 100 * 10;
+                make_interval_set(vec![(start, start + 3), (start + 6, start + 12)])
+            })
+            .collect();
+        let expected = sets
+            .iter()
+            .fold(IntervalSet::empty(), |acc, set| acc.union(set));
+        UNION_CALLS.with(|calls| calls.set(0));
+        let built = IntervalSet::build_from_sets(sets);
+        assert_eq!(UNION_CALLS.with(|calls| calls.get()), 0);
+        assert_eq!(built, expected);
+        assert_eq!(built.validate(), Ok(()));
+        assert_eq!(built.interval_count(), 101);
+
+        let cases = vec![
+            (1, vec![], vec![]),
+            (2, vec![vec![], vec![]], vec![]),
+            (3, vec![vec![(1, 4)], vec![]], vec![(1, 4)]),
+            (4, vec![vec![(5, 6)], vec![(1, 2)]], vec![(1, 2), (5, 6)]),
+            (5, vec![vec![(5, 6)], vec![(1, 4)]], vec![(1, 6)]),
+            (6, vec![vec![(0, 10)], vec![(2, 3), (5, 5)]], vec![(0, 10)]),
+        ];
+        for (id, sets, expected) in cases {
+            let sets: Vec<_> = sets.into_iter().map(make_interval_set).collect();
+            assert_eq!(
+                IntervalSet::build_from_sets(sets),
+                make_interval_set(expected),
+                "test #{} of build_from_sets",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_map_intervals() {
+        let chunks = |i: &Interval<i32>| {
+            (i.lower()..=i.upper())
+                .step_by(3)
+                .map(|l| Interval::new(l, (l + 2).min(i.upper())))
+                .collect::<Vec<_>>()
+        };
+        let pieces: Vec<_> = chunks(&Interval::new(0, 9))
+            .into_iter()
+            .map(|i| (i.lower(), i.upper()))
+            .collect();
+        assert_eq!(pieces, vec![(0, 2), (3, 5), (6, 8), (9, 9)]);
+        let cases = vec![
+            (1, vec![], vec![]),
+            (2, vec![(0, 9)], vec![(0, 9)]),
+            (
+                3,
+                vec![(-4, 0), (5, 5), (8, 20)],
+                vec![(-4, 0), (5, 5), (8, 20)],
+            ),
+        ];
+        for (id, a, expected) in cases {
+            test_op(
+                format!("test #{} of flat_map_intervals", id),
+                a,
+                |x| x.flat_map_intervals(chunks),
+                expected,
+            );
+        }
+        let shifted = make_interval_set(vec![(0, 3), (10, 12)]).flat_map_intervals(|i| {
+            vec![
+                *i,
+                Interval::new(i.lower() + 2, i.upper() + 2),
+                Interval::empty(),
+            ]
+        });
+        assert_eq!(shifted, make_interval_set(vec![(0, 5), (10, 14)]));
+    }
+
+    #[test]
+    fn test_cluster_by_gap() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            (1, vec![], 5, vec![]),
+            (
+                2,
+                vec![(0, 1), (3, 4), (100, 101)],
+                5,
+                vec![vec![(0, 1), (3, 4)], vec![(100, 101)]],
+            ),
+            (
+                3,
+                vec![(0, 1), (3, 4), (100, 101)],
+                1000,
+                vec![vec![(0, 1), (3, 4), (100, 101)]],
+            ),
+            (
+                4,
+                vec![(0, 1), (3, 4), (100, 101)],
+                0,
+                vec![vec![(0, 1)], vec![(3, 4)], vec![(100, 101)]],
+            ),
+            (
+                5,
+                vec![(0, 1), (4, 4), (8, 9)],
+                2,
+                vec![vec![(0, 1), (4, 4)], vec![(8, 9)]],
+            ),
+            (6, vec![(-5, 5)], 0, vec![vec![(-5, 5)]]),
+            (
+                7,
+                vec![
+                    (-2_000_000_000, -2_000_000_000),
+                    (2_000_000_000, 2_000_000_000),
+                ],
+                5,
+                vec![
+                    vec![(-2_000_000_000, -2_000_000_000)],
+                    vec![(2_000_000_000, 2_000_000_000)],
+                ],
+            ),
+            (
+                8,
+                vec![(min, min), (-1, -1), (max, max)],
+                max - 1,
+                vec![vec![(min, min), (-1, -1)], vec![(max, max)]],
+            ),
+            (
+                9,
+                vec![(min, min + 1), (max - 1, max)],
+                max,
+                vec![vec![(min, min + 1)], vec![(max - 1, max)]],
+            ),
+        ];
+        for (id, a, threshold, expected) in cases {
+            let a = make_interval_set(a);
+            let clusters = a.cluster_by_gap(threshold);
+            let expected: Vec<_> = expected.into_iter().map(make_interval_set).collect();
+            assert_eq!(clusters, expected, "test #{} of cluster_by_gap", id);
+            let union = clusters
+                .into_iter()
+                .fold(IntervalSet::empty(), |acc, c| acc.union(&c));
+            assert_eq!(union, a);
+        }
+    }
+
+    #[test]
+    fn test_clamp_count() {
+        let five = vec![(0, 1), (3, 4), (10, 11), (13, 13), (30, 31)];
+        let cases = vec![
+            (1, vec![], 2, vec![]),
+            (2, vec![], 0, vec![]),
+            (3, five.clone(), 5, five.clone()),
+            (4, five.clone(), 9, five.clone()),
+            (
+                5,
+                five.clone(),
+                4,
+                vec![(0, 4), (10, 11), (13, 13), (30, 31)],
+            ),
+            (6, five.clone(), 2, vec![(0, 13), (30, 31)]),
+            (7, five.clone(), 3, vec![(0, 4), (10, 13), (30, 31)]),
+            (8, five.clone(), 1, vec![(0, 31)]),
+            (9, five, 0, vec![(0, 31)]),
+            (
+                10,
+                vec![(-10, -9), (-5, -5), (0, 0), (5, 6)],
+                2,
+                vec![(-10, 0), (5, 6)],
+            ),
+        ];
+        for (id, a, max_intervals, expected) in cases {
+            let a = make_interval_set(a);
+            let res = a.clamp_count(max_intervals);
+            assert_eq!(
+                res,
+                make_interval_set(expected),
+                "test #{} of clamp_count",
+                id
+            );
+            assert!(a.is_subset(&res));
+            assert!(res.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_simplify() {
+        let gaps_1_1_4 = vec![(0, 1), (3, 4), (6, 6), (11, 12)];
+        let cases = vec![
+            (1, vec![], 3, vec![]),
+            (2, vec![(1, 5)], 3, vec![(1, 5)]),
+            (3, gaps_1_1_4.clone(), 0, gaps_1_1_4.clone()),
+            (4, gaps_1_1_4.clone(), 1, vec![(0, 4), (6, 6), (11, 12)]),
+            (5, gaps_1_1_4.clone(), 3, vec![(0, 6), (11, 12)]),
+            (6, gaps_1_1_4.clone(), 5, vec![(0, 6), (11, 12)]),
+            (7, gaps_1_1_4.clone(), 6, vec![(0, 12)]),
+            (8, gaps_1_1_4, 100, vec![(0, 12)]),
+            (
+                9,
+                vec![(-10, -9), (-7, -5), (0, 0), (5, 6)],
+                5,
+                vec![(-10, 0), (5, 6)],
+            ),
+        ];
+        for (id, a, max_error, expected) in cases {
+            let a = make_interval_set(a);
+            let res = a.simplify(max_error);
+            assert_eq!(res, make_interval_set(expected), "test #{} of simplify", id);
+            assert!(a.is_subset(&res));
+            assert!(res.size() - a.size() <= max_error);
+            assert!(res.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_interpolate_hull() {
+        let cases = vec![
+            (1, vec![], vec![], 1, 2, vec![]),
+            (2, vec![], vec![(3, 4), (8, 9)], 1, 2, vec![(3, 9)]),
+            (3, vec![(3, 4), (8, 9)], vec![], 1, 2, vec![(3, 9)]),
+            (
+                4,
+                vec![(0, 2), (6, 10)],
+                vec![(20, 40)],
+                0,
+                2,
+                vec![(0, 10)],
+            ),
+            (
+                5,
+                vec![(0, 2), (6, 10)],
+                vec![(20, 40)],
+                1,
+                2,
+                vec![(10, 25)],
+            ),
+            (
+                6,
+                vec![(0, 2), (6, 10)],
+                vec![(20, 40)],
+                2,
+                2,
+                vec![(20, 40)],
+            ),
+            (
+                7,
+                vec![(20, 40)],
+                vec![(0, 2), (6, 10)],
+                1,
+                2,
+                vec![(10, 25)],
+            ),
+            (8, vec![(-5, 0)], vec![(0, 5)], 1, 3, vec![(-4, 1)]),
+            (9, vec![(0, 5)], vec![(-5, 0)], 1, 3, vec![(-2, 3)]),
+        ];
+        for (id, a, b, num, den, expected) in cases {
+            let b = make_interval_set(b);
+            test_op(
+                format!("test #{} of interpolate_hull", id),
+                a,
+                |x| x.interpolate_hull(&b, num, den),
+                expected,
+            );
+        }
+        let a = IntervalSet::new(10u8, 20);
+        let b = IntervalSet::new(0u8, 4);
+        assert_eq!(a.interpolate_hull(&b, 1, 2), IntervalSet::new(5, 12));
+
+        let a = IntervalSet::new(0, 10);
+        let b = IntervalSet::new(0, 2_000_000_000);
+        assert_eq!(
+            a.interpolate_hull(&b, 3, 4),
+            IntervalSet::new(0, 1_500_000_002)
+        );
+        assert_eq!(
+            b.interpolate_hull(&a, 3, 4),
+            IntervalSet::new(0, 500_000_007)
+        );
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let a = IntervalSet::new(min, min + 10);
+        let b = IntervalSet::new(max - 10, max);
+        // The distances are `2 * max - 10`, not representable by an `i32`.
+        assert_eq!(a.interpolate_hull(&b, 1, 2), IntervalSet::new(-5, 5));
+        assert_eq!(b.interpolate_hull(&a, 1, 2), IntervalSet::new(-5, 5));
+        assert_eq!(a.interpolate_hull(&b, 1, 1), b);
+        assert_eq!(b.interpolate_hull(&a, 0, 1), b);
+        // A large denominator, whose square is not representable by an `i32`.
+        let den = 1_000_000_007;
+        assert_eq!(
+            a.interpolate_hull(&b, den - 1, den),
+            IntervalSet::new(max - 15, max - 5)
+        );
+        let fractions = [
+            (min, max, 1, 3),
+            (min, max, 2, 3),
+            (-7, 2_000_000_000, 999_999_999, 1_000_000_000),
+            (-2_000_000_000, 2_000_000_000, 123_456_789, 987_654_321),
+            (5, 2_000_000_000, 65_537, 2_147_483_647),
+        ];
+        for (lo, hi, num, den) in IntoIterator::into_iter(fractions) {
+            let expected = |from: i64, to: i64| {
+                let d = (to - from) as i128 * num as i128;
+                if from <= to {
+                    from + (d.div_euclid(den as i128)) as i64
+                } else {
+                    from - ((-d + den as i128 - 1).div_euclid(den as i128)) as i64
+                }
+            };
+            let (a, b) = (IntervalSet::singleton(lo), IntervalSet::singleton(hi));
+            assert_eq!(
+                a.interpolate_hull(&b, num, den).lower() as i64,
+                expected(lo as i64, hi as i64)
+            );
+            assert_eq!(
+                b.interpolate_hull(&a, num, den).lower() as i64,
+                expected(hi as i64, lo as i64)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interpolate_hull_out_of_range() {
+        let a = IntervalSet::new(0, 1);
+        a.interpolate_hull(&a, 3, 2);
+    }
+
+    #[test]
+    fn test_hull_with() {
+        let cases = vec![
+            (1, vec![], vec![], vec![]),
+            (2, vec![], vec![(1, 2), (7, 9)], vec![(1, 9)]),
+            (3, vec![(1, 2)], vec![(1, 2)], vec![(1, 2)]),
+            (4, vec![(1, 2), (7, 9)], vec![(4, 5)], vec![(1, 9)]),
+            (
+                5,
+                vec![(-3, -1), (4, 5)],
+                vec![(7, 9), (11, 12)],
+                vec![(-3, 12)],
+            ),
+        ];
+
+        for (id, a, b, expected) in cases {
+            test_binary_op_sym(
+                format!("test #{} of hull_with", id),
+                a,
+                b,
+                |x, y| x.hull_with(y),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_into_chunks() {
+        let cases = vec![
+            (1, vec![], 4, vec![]),
+            (
+                2,
+                vec![(0, 9)],
+                4,
+                vec![vec![(0, 3)], vec![(4, 7)], vec![(8, 9)]],
+            ),
+            (3, vec![(0, 7)], 4, vec![vec![(0, 3)], vec![(4, 7)]]),
+            (4, vec![(0, 9)], 10, vec![vec![(0, 9)]]),
+            (
+                5,
+                vec![(0, 1), (4, 4), (6, 8)],
+                2,
+                vec![vec![(0, 1)], vec![(4, 4), (6, 6)], vec![(7, 8)]],
+            ),
+            (
+                6,
+                vec![(-5, -4), (0, 0), (3, 3)],
+                1,
+                vec![vec![(-5, -5)], vec![(-4, -4)], vec![(0, 0)], vec![(3, 3)]],
+            ),
+        ];
+
+        for (id, a, max_size, expected) in cases {
+            println!("Info: test #{} of split_into_chunks.", id);
+            let chunks = make_interval_set(a).split_into_chunks(max_size);
+            let expected: Vec<_> = expected.into_iter().map(make_interval_set).collect();
+            assert_eq!(chunks, expected);
+        }
+
+        let whole = IntervalSet::<i8>::whole();
+        let chunks = whole.split_into_chunks(200);
+        assert_eq!(
+            chunks,
+            vec![IntervalSet::new(-127, 72), IntervalSet::new(73, 127)]
+        );
+    }
+
+    #[test]
+    fn test_trim_to_size() {
+        let cases = vec![
+            (1, vec![], 0, vec![]),
+            (2, vec![], 3, vec![]),
+            (3, vec![(0, 4), (10, 14)], 0, vec![]),
+            (4, vec![(0, 4), (10, 14)], 1, vec![(0, 0)]),
+            (5, vec![(0, 4), (10, 14)], 5, vec![(0, 4)]),
+            (6, vec![(0, 4), (10, 14)], 7, vec![(0, 4), (10, 11)]),
+            (7, vec![(0, 4), (10, 14)], 10, vec![(0, 4), (10, 14)]),
+            (8, vec![(0, 4), (10, 14)], 11, vec![(0, 4), (10, 14)]),
+            (
+                9,
+                vec![(-5, -5), (0, 4), (10, 14)],
+                3,
+                vec![(-5, -5), (0, 1)],
+            ),
+        ];
+
+        for (id, a, max, expected) in cases {
+            test_op(
+                format!("test #{} of trim_to_size", id),
+                a,
+                |x| x.trim_to_size(max),
+                expected,
+            );
+        }
+
+        let whole = IntervalSet::<i8>::whole();
+        assert_eq!(whole.trim_to_size(254), IntervalSet::new(-127, 126));
+    }
+
+    #[test]
+    fn test_scale_round() {
+        use RoundMode::*;
+        let cases = vec![
+            (1, vec![], 1, 2, Outward, vec![]),
+            (2, vec![(1, 3), (10, 12)], 1, 2, Floor, vec![(0, 1), (5, 6)]),
+            (3, vec![(1, 3), (10, 12)], 1, 2, Ceil, vec![(1, 2), (5, 6)]),
+            (
+                4,
+                vec![(1, 3), (10, 12)],
+                1,
+                2,
+                Nearest,
+                vec![(1, 2), (5, 6)],
+            ),
+            (
+                5,
+                vec![(1, 3), (10, 12)],
+                1,
+                2,
+                Outward,
+                vec![(0, 2), (5, 6)],
+            ),
+            (
+                6,
+                vec![(-3, -1), (4, 5)],
+                1,
+                2,
+                Floor,
+                vec![(-2, -1), (2, 2)],
+            ),
+            (7, vec![(-3, -1), (4, 5)], 1, 2, Ceil, vec![(-1, 0), (2, 3)]),
+            (
+                8,
+                vec![(-3, -1), (4, 5)],
+                1,
+                2,
+                Nearest,
+                vec![(-1, 0), (2, 3)],
+            ),
+            (
+                9,
+                vec![(-3, -1), (4, 5)],
+                -1,
+                2,
+                Outward,
+                vec![(-3, -2), (0, 2)],
+            ),
+            (
+                10,
+                vec![(-3, -1), (4, 5)],
+                1,
+                -2,
+                Outward,
+                vec![(-3, -2), (0, 2)],
+            ),
+            (11, vec![(1, 2), (4, 5)], 1, 3, Outward, vec![(0, 2)]),
+            (12, vec![(1, 2), (4, 5)], 2, 1, Floor, vec![(2, 4), (8, 10)]),
+            (13, vec![(1, 2), (4, 5)], 0, 7, Floor, vec![(0, 0)]),
+            (14, vec![(1, 4)], 2, 3, Nearest, vec![(1, 3)]),
+        ];
+
+        for (id, a, num, den, mode, expected) in cases {
+            test_op(
+                format!("test #{} of scale_round", id),
+                a,
+                |x| x.scale_round(num, den, mode),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn test_scale_round_outward_is_sound() {
+        let sets = vec![
+            vec![(-20, -13), (-7, 2), (5, 5), (9, 30)],
+            vec![(-1, 1), (3, 4), (17, 19)],
+        ];
+        let fractions = vec![(1, 2), (2, 3), (-5, 7), (7, 4), (3, -8), (0, 3)];
+        for intervals in sets {
+            let set = make_interval_set(intervals);
+            for &(num, den) in &fractions {
+                let scaled = set.scale_round(num, den, RoundMode::Outward);
+                for interval in &set {
+                    for v in interval.lower()..=interval.upper() {
+                        let floor = Integer::div_floor(&(v * num), &den);
+                        let ceil = Integer::div_ceil(&(v * num), &den);
+                        assert!(
+                            scaled.contains(&floor),
+                            "{} * {}/{} lost in {}",
+                            v,
+                            num,
+                            den,
+                            scaled
+                        );
+                        assert!(
+                            scaled.contains(&ceil),
+                            "{} * {}/{} lost in {}",
+                            v,
+                            num,
+                            den,
+                            scaled
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tuple_iter() {
+        let input = vec![(-4, -2), (1, 3), (7, 7)];
+        let interval_set = input.clone().to_interval_set();
+        assert_eq!(interval_set.tuple_iter().collect::<Vec<_>>(), input);
+        let round_trip = interval_set
+            .tuple_iter()
+            .collect::<Vec<_>>()
+            .to_interval_set();
+        assert_eq!(round_trip, interval_set);
+        assert_eq!(interval_set.into_tuple_iter().collect::<Vec<_>>(), input);
+        assert_eq!(IntervalSet::<i32>::empty().into_tuple_iter().next(), None);
+    }
+
+    #[test]
+    fn test_boundary_points() {
+        let interval_set = make_interval_set(vec![(-4, -2), (1, 3), (7, 7)]);
+        assert_eq!(
+            interval_set.boundary_points().collect::<Vec<_>>(),
+            vec![-4, -2, 1, 3, 7, 7]
+        );
+        assert_eq!(
+            make_interval_set(vec![(1, 3), (7, 9)])
+                .boundary_points()
+                .collect::<Vec<_>>(),
+            vec![1, 3, 7, 9]
+        );
+        assert_eq!(IntervalSet::<i32>::empty().boundary_points().next(), None);
+    }
+
+    #[test]
+    fn test_into_interval_vec() {
+        let interval_set = make_interval_set(vec![(7, 7), (-4, -2), (1, 3), (4, 5)]);
+        let expected: Vec<_> = interval_set.iter().cloned().collect();
+        let intervals = interval_set.into_interval_vec();
+        assert_eq!(intervals, expected);
+        assert_eq!(
+            intervals,
+            vec![
+                Interval::new(-4, -2),
+                Interval::new(1, 5),
+                Interval::new(7, 7)
+            ]
+        );
+        assert!(intervals
+            .windows(2)
+            .all(|pair| pair[0].upper() < pair[1].lower()));
+        assert!(IntervalSet::<i32>::empty().into_interval_vec().is_empty());
+    }
+
+    #[test]
+    fn test_as_slice() {
+        fn total_size<S: AsRef<[Interval<i32>]>>(intervals: S) -> u32 {
+            intervals.as_ref().iter().map(|i| i.size()).sum()
+        }
+        fn first<S: Borrow<[Interval<i32>]>>(intervals: &S) -> Option<&Interval<i32>> {
+            intervals.borrow().first()
+        }
+        let interval_set = make_interval_set(vec![(1, 2), (5, 7)]);
+        assert_eq!(total_size(&interval_set), interval_set.size());
+        assert_eq!(first(&interval_set), Some(&Interval::new(1, 2)));
+        let empty = IntervalSet::<i32>::empty();
+        assert_eq!(total_size(&empty), 0);
+        assert_eq!(first(&empty), None);
+    }
+
+    #[test]
+    fn test_iter_intervals_rev() {
+        let interval_set = make_interval_set(vec![(1, 2), (5, 6), (9, 9)]);
+        let expected = vec![(9, 9), (5, 6), (1, 2)];
+        let reversed: Vec<_> = interval_set
+            .iter_intervals_rev()
+            .map(|i| (i.lower(), i.upper()))
+            .collect();
+        assert_eq!(reversed, expected);
+        let reversed: Vec<_> = interval_set
+            .into_iter_rev()
+            .map(|i| (i.lower(), i.upper()))
+            .collect();
+        assert_eq!(reversed, expected);
+        assert_eq!(
+            IntervalSet::<i32>::empty().iter_intervals_rev().next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_iter_values() {
+        let interval_set = make_interval_set(vec![(-1, 1), (4, 4), (7, 8)]);
+        let values: Vec<_> = interval_set.iter_values().collect();
+        assert_eq!(values, vec![-1, 0, 1, 4, 7, 8]);
+        assert_eq!(IntervalSet::<i32>::empty().iter_values().next(), None);
+        let max = <u8 as Width>::max_value();
+        let edge = IntervalSet::new(max - 1, max);
+        assert_eq!(edge.iter_values().collect::<Vec<_>>(), vec![max - 1, max]);
+    }
+
+    #[test]
+    fn test_iter_values_in() {
+        let interval_set = make_interval_set(vec![(0, 5), (10, 15)]);
+        let cases = vec![
+            (1, 3, 11, vec![3, 4, 5, 10, 11]),
+            (2, -5, 1, vec![0, 1]),
+            (3, 6, 9, vec![]),
+            (4, 14, 30, vec![14, 15]),
+            (5, 16, 30, vec![]),
+            (6, 4, 3, vec![]),
+            (7, 5, 10, vec![5, 10]),
+        ];
+        for (id, lb, ub, expected) in cases {
+            let values: Vec<_> = interval_set.iter_values_in(lb, ub).collect();
+            assert_eq!(values, expected, "test #{} of iter_values_in", id);
+        }
+        let max = <u8 as Width>::max_value();
+        let edge = IntervalSet::new(max - 2, max);
+        let values: Vec<_> = edge.iter_values_in(max - 1, max).collect();
+        assert_eq!(values, vec![max - 1, max]);
+        assert_eq!(
+            IntervalSet::<i32>::empty().iter_values_in(0, 5).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retain_values() {
+        fn check<F: Fn(&i32) -> bool>(
+            id: u32,
+            a: Vec<(i32, i32)>,
+            f: F,
+            expected: Vec<(i32, i32)>,
+        ) {
+            test_op(
+                format!("test #{} of retain_values", id),
+                a,
+                |x| x.retain_values(&f),
+                expected,
+            );
+        }
+        check(1, vec![], |_| true, vec![]);
+        check(
+            2,
+            vec![(0, 5)],
+            |v| v % 2 == 0,
+            vec![(0, 0), (2, 2), (4, 4)],
+        );
+        check(
+            3,
+            vec![(-3, 3), (8, 9)],
+            |v| *v != 0,
+            vec![(-3, -1), (1, 3), (8, 9)],
+        );
+        check(4, vec![(-3, 3), (8, 9)], |_| true, vec![(-3, 3), (8, 9)]);
+        check(5, vec![(-3, 3), (8, 9)], |_| false, vec![]);
+        check(
+            6,
+            vec![(0, 4), (6, 9)],
+            |v| *v < 2 || *v > 6,
+            vec![(0, 1), (7, 9)],
+        );
+    }
+
+    #[test]
+    fn test_partition() {
+        fn check<F: Fn(&i32) -> bool>(
+            id: u32,
+            a: Vec<(i32, i32)>,
+            f: F,
+            expected_matching: Vec<(i32, i32)>,
+            expected_others: Vec<(i32, i32)>,
+        ) {
+            let a = make_interval_set(a);
+            let (matching, others) = a.partition(&f);
+            assert_eq!(
+                matching,
+                make_interval_set(expected_matching),
+                "test #{} of partition",
+                id
+            );
+            assert_eq!(
+                others,
+                make_interval_set(expected_others),
+                "test #{} of partition",
+                id
+            );
+            assert_eq!(matching, a.retain_values(&f), "test #{} of partition", id);
+            assert!(matching.is_disjoint(&others), "test #{} of partition", id);
+            assert_eq!(matching.union(&others), a, "test #{} of partition", id);
+        }
+        check(1, vec![], |_| true, vec![], vec![]);
+        check(
+            2,
+            vec![(0, 5)],
+            |v| v % 2 == 0,
+            vec![(0, 0), (2, 2), (4, 4)],
+            vec![(1, 1), (3, 3), (5, 5)],
+        );
+        check(
+            3,
+            vec![(-3, 3), (8, 9)],
+            |v| *v != 0,
+            vec![(-3, -1), (1, 3), (8, 9)],
+            vec![(0, 0)],
+        );
+        check(
+            4,
+            vec![(-3, 3), (8, 9)],
+            |_| true,
+            vec![(-3, 3), (8, 9)],
+            vec![],
+        );
+        check(
+            5,
+            vec![(-3, 3), (8, 9)],
+            |_| false,
+            vec![],
+            vec![(-3, 3), (8, 9)],
+        );
+        check(
+            6,
+            vec![(0, 4), (6, 9)],
+            |v| *v < 2 || *v > 6,
+            vec![(0, 1), (7, 9)],
+            vec![(2, 4), (6, 6)],
+        );
     }
 
-    fn test_result(test_id: String, result: &IntervalSet<i32>, expected: &IntervalSet<i32>) {
-        assert!(
-            result.intervals == expected.intervals,
-            "{} | {} is different from the expected value: {}.",
-            test_id,
-            result,
-            expected
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample_without_replacement() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(7);
+        let interval_set = make_interval_set(vec![(-5, -3), (0, 0), (10, 15)]);
+        let all: Vec<_> = interval_set.iter_values().collect();
+        for k in [10, 11, 100] {
+            let sample = interval_set.sample_without_replacement(k, &mut rng);
+            assert_eq!(sample, all, "k = {}", k);
+        }
+        for k in 0..10 {
+            for _ in 0..20 {
+                let sample = interval_set.sample_without_replacement(k, &mut rng);
+                assert_eq!(sample.len(), k);
+                assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+                assert!(sample.iter().all(|v| interval_set.contains(v)));
+            }
+        }
+        // Every value can be drawn.
+        let mut drawn = IntervalSet::empty();
+        for _ in 0..200 {
+            for v in interval_set.sample_without_replacement(1, &mut rng) {
+                drawn = drawn.union(&v);
+            }
+        }
+        assert_eq!(drawn, interval_set);
+        assert!(IntervalSet::<i32>::empty()
+            .sample_without_replacement(3, &mut rng)
+            .is_empty());
+        let whole = IntervalSet::<i64>::whole();
+        let sample = whole.sample_without_replacement(1000, &mut rng);
+        assert_eq!(sample.len(), 1000);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_as_ranges() {
+        let interval_set = make_interval_set(vec![(-4, -2), (1, 3), (7, 7)]);
+        let ranges: Vec<_> = interval_set.as_ranges().collect();
+        assert_eq!(ranges, vec![-4..=-2, 1..=3, 7..=7]);
+        let values: Vec<_> = interval_set.as_ranges().flatten().collect();
+        assert_eq!(values, interval_set.iter_values().collect::<Vec<_>>());
+        assert_eq!(IntervalSet::<i32>::empty().as_ranges().next(), None);
+    }
+
+    #[test]
+    fn test_cartesian_pairs() {
+        let sets = vec![
+            vec![],
+            vec![(0, 0)],
+            vec![(1, 3), (6, 6)],
+            vec![(-4, -2), (2, 3), (10, 11)],
+        ];
+        for a in &sets {
+            for b in &sets {
+                let a = make_interval_set(a.clone());
+                let b = make_interval_set(b.clone());
+                let pairs: Vec<_> = a.cartesian_pairs(&b).collect();
+                assert_eq!(pairs.len() as u32, a.size() * b.size());
+                for (x, y) in pairs {
+                    assert!(a.contains(&x) && b.contains(&y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ranges_to_interval_set() {
+        let empty = RangeInclusive::new(5, 0);
+        assert_eq!((0..=5).to_interval_set(), make_interval_set(vec![(0, 5)]));
+        assert_eq!(empty.clone().to_interval_set(), make_interval_set(vec![]));
+        assert_eq!(
+            [8..=10, -2..=1, 0..=3, 9..=9].to_interval_set(),
+            make_interval_set(vec![(-2, 3), (8, 10)])
+        );
+        assert_eq!(
+            [4..=5, 1..=3, 7..=7, empty].to_interval_set(),
+            make_interval_set(vec![(1, 5), (7, 7)])
         );
+        assert!([4..=5, 1..=3, 7..=7].to_interval_set().validate().is_ok());
+
+        let ranges = vec![
+            8..=10,
+            -2..=1,
+            0..=3,
+            9..=9,
+            11..=12,
+            RangeInclusive::new(5, 0),
+        ];
+        let expected = make_interval_set(vec![(-2, 3), (8, 12)]);
+        assert_eq!(IntervalSet::from_ranges(ranges.iter().cloned()), expected);
+        assert_eq!(IntervalSet::from_ranges(ranges), expected);
+        assert_eq!(
+            IntervalSet::from_ranges(vec![0..=2, 5..=6]),
+            make_interval_set(vec![(0, 2), (5, 6)])
+        );
+        assert!(IntervalSet::<i32>::from_ranges(vec![]).is_empty());
     }
 
-    fn test_binary_op_sym<F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: Vec<(i32, i32)>,
-        op: F,
-        expected: Vec<(i32, i32)>,
-    ) where
-        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> IntervalSet<i32>,
-    {
-        test_binary_op(
-            test_id.clone(),
-            a.clone(),
-            b.clone(),
-            |i, j| op(i, j),
-            expected.clone(),
+    #[test]
+    fn test_from_values() {
+        let cases = vec![
+            (1, Vec::<i32>::new(), vec![]),
+            (2, vec![4], vec![(4, 4)]),
+            (3, vec![5, 1, 2, 3, 5, 9], vec![(1, 3), (5, 5), (9, 9)]),
+            (4, vec![3, 3, 3], vec![(3, 3)]),
+            (5, vec![2, 0, -1, 1, 7, 6], vec![(-1, 2), (6, 7)]),
+        ];
+        for (id, values, expected) in cases {
+            assert_eq!(
+                IntervalSet::from(&values[..]),
+                make_interval_set(expected),
+                "test #{} of from values",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_num_intervals_in_range() {
+        let interval_set = make_interval_set(vec![(1, 3), (6, 6), (9, 12)]);
+        let cases = vec![
+            (1, -5, 0, 0),
+            (2, -5, 1, 1),
+            (3, 2, 10, 3),
+            (4, 3, 6, 2),
+            (5, 4, 5, 0),
+            (6, 4, 8, 1),
+            (7, 12, 20, 1),
+            (8, 13, 20, 0),
+            (9, 10, 2, 0),
+            (10, 0, 20, 3),
+        ];
+        for (id, lb, ub, expected) in cases {
+            assert_eq!(
+                interval_set.num_intervals_in_range(lb, ub),
+                expected,
+                "test #{} of num_intervals_in_range",
+                id
+            );
+        }
+        assert_eq!(IntervalSet::<i32>::empty().num_intervals_in_range(0, 10), 0);
+    }
+
+    #[test]
+    fn test_disjoint_union() {
+        let cases = vec![
+            (1, vec![], vec![], vec![]),
+            (2, vec![(1, 3)], vec![], vec![(1, 3)]),
+            (3, vec![(1, 3)], vec![(4, 5)], vec![(1, 5)]),
+            (4, vec![(1, 3)], vec![(5, 6)], vec![(1, 3), (5, 6)]),
+            (
+                5,
+                vec![(0, 0), (4, 6), (20, 30)],
+                vec![(1, 2), (8, 9), (11, 11), (31, 40)],
+                vec![(0, 2), (4, 6), (8, 9), (11, 11), (20, 40)],
+            ),
+            (6, vec![(1, 1), (3, 3)], vec![(2, 2)], vec![(1, 3)]),
+        ];
+        for (id, a, b, expected) in cases {
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            let expected = make_interval_set(expected);
+            assert_eq!(
+                a.disjoint_union(&b),
+                expected,
+                "test #{} of disjoint_union",
+                id
+            );
+            assert_eq!(
+                b.disjoint_union(&a),
+                expected,
+                "test #{} of disjoint_union",
+                id
+            );
+            assert_eq!(a.union(&b), expected, "test #{} of disjoint_union", id);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`disjoint_union` expects disjoint interval sets.")]
+    fn test_disjoint_union_overlapping() {
+        let a = make_interval_set(vec![(1, 5), (10, 12)]);
+        let b = make_interval_set(vec![(6, 7), (12, 20)]);
+        let _ = a.disjoint_union(&b);
+    }
+
+    #[test]
+    fn test_to_interval_set_from_sets() {
+        let values = vec![40, -3, 7, 8, 9, -2, 0, 41, 12, -1, 39, 100];
+        let expected = make_interval_set(vec![(-3, 0), (7, 9), (12, 12), (39, 41), (100, 100)]);
+        // Rotating the insertion order changes the layout, hence the iteration order, of the hash sets.
+        for shift in 0..values.len() {
+            let mut rotated = values.clone();
+            rotated.rotate_left(shift);
+            let hash_set: HashSet<i32> = rotated.iter().cloned().collect();
+            assert_eq!(hash_set.to_interval_set(), expected, "shift {}", shift);
+            let mut hash_set = HashSet::with_capacity(1000);
+            hash_set.extend(rotated.iter().cloned());
+            assert_eq!(hash_set.to_interval_set(), expected, "shift {}", shift);
+            let btree_set: BTreeSet<i32> = rotated.into_iter().collect();
+            assert_eq!(btree_set.to_interval_set(), expected, "shift {}", shift);
+        }
+        assert!(HashSet::<u8>::new().to_interval_set().is_empty());
+        assert!(BTreeSet::<u8>::new().to_interval_set().is_empty());
+        let max = <u8 as Width>::max_value();
+        let btree_set: BTreeSet<u8> = vec![max - 1, 0, max].into_iter().collect();
+        assert_eq!(
+            btree_set.to_interval_set(),
+            IntervalSet::new(0, 0).union(&IntervalSet::new(max - 1, max))
         );
-        test_binary_op(test_id, b, a, op, expected);
     }
 
-    fn test_binary_op<F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: Vec<(i32, i32)>,
-        op: F,
-        expected: Vec<(i32, i32)>,
-    ) where
-        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> IntervalSet<i32>,
-    {
-        println!("Info: {}.", test_id);
-        let a = make_interval_set(a);
-        let b = make_interval_set(b);
-        let expected = make_interval_set(expected);
-        test_result(test_id, &op(&a, &b), &expected);
+    #[test]
+    fn test_covers_range() {
+        let interval_set = make_interval_set(vec![(1, 5), (8, 10), (12, 20)]);
+        let cases = vec![
+            (1, 2, 4, true),
+            (2, 1, 5, true),
+            (3, 8, 10, true),
+            (4, 13, 13, true),
+            (5, 0, 3, false),
+            (6, 4, 9, false),
+            (7, 8, 12, false),
+            (8, 6, 7, false),
+            (9, 18, 21, false),
+            (10, 25, 30, false),
+            (11, 7, 6, true),
+            (12, 30, -30, true),
+        ];
+        for (id, lb, ub, expected) in cases {
+            assert_eq!(
+                interval_set.covers_range(lb, ub),
+                expected,
+                "test #{} of covers_range",
+                id
+            );
+            if lb <= ub {
+                assert_eq!(
+                    IntervalSet::new(lb, ub).is_subset(&interval_set),
+                    expected,
+                    "test #{} of covers_range",
+                    id
+                );
+            }
+        }
+        assert!(!IntervalSet::<i32>::empty().covers_range(0, 0));
+        assert!(IntervalSet::<i32>::empty().covers_range(1, 0));
     }
 
-    fn test_binary_value_op<F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: i32,
-        op: F,
-        expected: Vec<(i32, i32)>,
-    ) where
-        F: Fn(&IntervalSet<i32>, i32) -> IntervalSet<i32>,
-    {
-        println!("Info: {}.", test_id);
-        let a = make_interval_set(a);
-        let expected = make_interval_set(expected);
-        test_result(test_id, &op(&a, b), &expected);
+    #[test]
+    fn test_contains_without_arithmetic() {
+        // A bound supporting comparisons but no arithmetic.
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Tick(u32);
+
+        impl Width for Tick {
+            type Output = u32;
+            fn min_value() -> Tick {
+                Tick(0)
+            }
+            fn max_value() -> Tick {
+                Tick(u32::MAX - 1)
+            }
+            fn width(lower: &Tick, upper: &Tick) -> u32 {
+                upper.0 - lower.0 + 1
+            }
+        }
+
+        let interval_set = IntervalSet {
+            intervals: vec![
+                Interval::new(Tick(1), Tick(3)),
+                Interval::new(Tick(7), Tick(8)),
+            ],
+            size: 5,
+        };
+        let expected = [
+            false, true, true, true, false, false, false, true, true, false,
+        ];
+        for (v, &contained) in expected.iter().enumerate() {
+            assert_eq!(interval_set.contains(&Tick(v as u32)), contained, "{}", v);
+        }
     }
 
-    fn test_binary_bool_op_sym<F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: Vec<(i32, i32)>,
-        op: F,
-        expected: bool,
-    ) where
-        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> bool,
-    {
-        test_binary_bool_op(
-            test_id.clone(),
-            a.clone(),
-            b.clone(),
-            |i, j| op(i, j),
-            expected,
+    #[test]
+    fn test_bound_refs() {
+        let interval_set = make_interval_set(vec![(-5, 11), (20, 30)]);
+        let lower = interval_set.lower_ref().unwrap();
+        let upper = interval_set.upper_ref().unwrap();
+        assert_eq!(*lower, interval_set.lower());
+        assert_eq!(*upper, interval_set.upper());
+        assert!(std::ptr::eq(lower, interval_set.intervals[0].lower_ref()));
+        assert!(std::ptr::eq(upper, interval_set.intervals[1].upper_ref()));
+        let singleton = IntervalSet::singleton(7);
+        assert_eq!(singleton.lower_ref(), Some(&7));
+        assert_eq!(singleton.upper_ref(), Some(&7));
+        assert_eq!(IntervalSet::<i32>::empty().lower_ref(), None);
+        assert_eq!(IntervalSet::<i32>::empty().upper_ref(), None);
+    }
+
+    #[test]
+    fn test_partition_index() {
+        let interval_set = make_interval_set(vec![(1, 3), (6, 6), (9, 12)]);
+        let cases = vec![
+            (-5, 0),
+            (1, 0),
+            (2, 1),
+            (4, 1),
+            (6, 1),
+            (7, 2),
+            (9, 2),
+            (11, 3),
+            (20, 3),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                interval_set.partition_index(&value),
+                expected,
+                "partition index of {}",
+                value
+            );
+        }
+        assert_eq!(IntervalSet::<i32>::empty().partition_index(&0), 0);
+    }
+
+    #[test]
+    fn test_set_op_updates() {
+        let sets = vec![
+            vec![],
+            vec![(0, 10)],
+            vec![(1, 3), (6, 6), (9, 12)],
+            vec![(-5, 2), (5, 7), (11, 20)],
+            vec![(4, 5), (13, 13)],
+            vec![(0, 3), (7, 7), (10, 12)],
+        ];
+        for a in &sets {
+            for b in &sets {
+                let a = make_interval_set(a.clone());
+                let b = make_interval_set(b.clone());
+                let mut res = a.clone();
+                res.difference_update(&b);
+                assert_eq!(res, a.difference(&b));
+                assert_eq!(res.size(), a.difference(&b).size());
+                let mut res = a.clone();
+                res.intersection_update(&b);
+                assert_eq!(res, a.intersection(&b));
+                assert_eq!(res.size(), a.intersection(&b).size());
+                let mut res = a.clone();
+                res.symmetric_difference_update(&b);
+                assert_eq!(res, a.symmetric_difference(&b));
+                assert_eq!(res.size(), a.symmetric_difference(&b).size());
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_op_updates_reuse_capacity() {
+        let original = make_interval_set((0..20).map(|i| (i * 4, i * 4 + 1)).collect());
+        let holes = make_interval_set(vec![(10, 30), (50, 52)]);
+        let shift = make_interval_set((0..20).map(|i| (i * 4 + 1, i * 4 + 2)).collect());
+        let whole = IntervalSet::whole();
+        let mut interval_set = original.clone();
+        // With enough room for the intervals and the result of each sweep, the storage is never replaced.
+        interval_set.intervals.reserve(256);
+        let capacity = interval_set.intervals.capacity();
+        for _ in 0..10 {
+            interval_set.intersection_update(&whole);
+            interval_set.difference_update(&holes);
+            // Applied twice, the symmetric difference gives back the same set.
+            interval_set.symmetric_difference_update(&shift);
+            interval_set.symmetric_difference_update(&shift);
+            assert_eq!(interval_set.intervals.capacity(), capacity);
+        }
+        assert_eq!(interval_set, original.difference(&holes));
+    }
+
+    #[test]
+    fn test_set_op_into_scratch_reuses_capacity() {
+        // Alternating between two buffers replaces the `_update` methods without allocating in the loop.
+        let mut interval_set = make_interval_set((0..20).map(|i| (i * 4, i * 4 + 1)).collect());
+        let mut scratch = IntervalSet::empty();
+        let holes = make_interval_set(vec![(10, 30)]);
+        let whole = IntervalSet::whole();
+        let mut capacities = None;
+        for _ in 0..10 {
+            interval_set.intersection_into(&whole, &mut scratch);
+            std::mem::swap(&mut interval_set, &mut scratch);
+            interval_set.difference_into(&holes, &mut scratch);
+            std::mem::swap(&mut interval_set, &mut scratch);
+            interval_set.union_into(&holes, &mut scratch);
+            std::mem::swap(&mut interval_set, &mut scratch);
+            // The buffers swap roles at each iteration.
+            let (a, b) = (
+                interval_set.intervals.capacity(),
+                scratch.intervals.capacity(),
+            );
+            let current = (min(a, b), max(a, b));
+            assert_eq!(*capacities.get_or_insert(current), current);
+        }
+        assert_eq!(
+            interval_set,
+            make_interval_set((0..20).map(|i| (i * 4, i * 4 + 1)).collect()).union(&holes)
         );
-        test_binary_bool_op(test_id, b, a, op, expected);
     }
 
-    fn test_binary_bool_op<F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: Vec<(i32, i32)>,
-        op: F,
-        expected: bool,
-    ) where
-        F: Fn(&IntervalSet<i32>, &IntervalSet<i32>) -> bool,
-    {
-        println!("Info: {}.", test_id);
-        let a = make_interval_set(a);
-        let b = make_interval_set(b);
-        assert_eq!(op(&a, &b), expected);
+    #[test]
+    fn test_set_op_into() {
+        let sets = vec![
+            vec![],
+            vec![(0, 10)],
+            vec![(1, 3), (6, 6), (9, 12)],
+            vec![(-5, 2), (5, 7), (11, 20)],
+        ];
+        let mut out = make_interval_set(vec![(30, 40), (50, 60)]);
+        for a in &sets {
+            for b in &sets {
+                let a = make_interval_set(a.clone());
+                let b = make_interval_set(b.clone());
+                a.union_into(&b, &mut out);
+                assert_eq!(out, a.union(&b));
+                assert_eq!(out.size(), a.union(&b).size());
+                a.intersection_into(&b, &mut out);
+                assert_eq!(out, a.intersection(&b));
+                assert_eq!(out.size(), a.intersection(&b).size());
+                a.difference_into(&b, &mut out);
+                assert_eq!(out, a.difference(&b));
+                assert_eq!(out.size(), a.difference(&b).size());
+            }
+        }
     }
 
-    fn test_binary_value_bool_op<V, F>(
-        test_id: String,
-        a: Vec<(i32, i32)>,
-        b: V,
-        op: F,
-        expected: bool,
-    ) where
-        F: Fn(&IntervalSet<i32>, &V) -> bool,
-    {
-        println!("Info: {}.", test_id);
-        let a = make_interval_set(a);
-        assert_eq!(op(&a, &b), expected);
+    #[test]
+    fn test_intersection_into_reuses_capacity() {
+        let mut out = IntervalSet::empty();
+        let mut capacities = vec![out.intervals.capacity()];
+        for shift in 0..20 {
+            let a = make_interval_set((0..20).map(|i| (i * 4, i * 4 + 1)).collect());
+            let b = make_interval_set(
+                (0..20)
+                    .map(|i| (i * 4 + shift, i * 4 + shift + 2))
+                    .collect(),
+            );
+            a.intersection_into(&b, &mut out);
+            assert_eq!(out, a.intersection(&b));
+            if out.intervals.capacity() != *capacities.last().unwrap() {
+                capacities.push(out.intervals.capacity());
+            }
+        }
+        assert_eq!(capacities.len(), 2, "capacities: {:?}", capacities);
     }
 
-    fn test_op<F>(test_id: String, a: Vec<(i32, i32)>, op: F, expected: Vec<(i32, i32)>)
-    where
-        F: Fn(&IntervalSet<i32>) -> IntervalSet<i32>,
-    {
-        println!("Info: {}.", test_id);
-        let a = make_interval_set(a);
-        let expected = make_interval_set(expected);
-        let result = op(&a);
-        test_result(test_id, &result, &expected);
+    #[test]
+    fn test_windows() {
+        let cases = vec![
+            (1, vec![], 3, vec![]),
+            (2, vec![(0, 4)], 3, vec![(0, 2), (1, 3), (2, 4)]),
+            (3, vec![(0, 4)], 5, vec![(0, 4)]),
+            (4, vec![(0, 4)], 6, vec![]),
+            (5, vec![(0, 4)], 0, vec![]),
+            (
+                6,
+                vec![(-3, -2), (1, 1), (5, 7)],
+                1,
+                vec![(-3, -3), (-2, -2), (1, 1), (5, 5), (6, 6), (7, 7)],
+            ),
+            (
+                7,
+                vec![(-3, -2), (1, 1), (5, 7)],
+                2,
+                vec![(-3, -2), (5, 6), (6, 7)],
+            ),
+        ];
+        for (id, a, len, expected) in cases {
+            let windows: Vec<_> = make_interval_set(a)
+                .windows(len)
+                .map(|i| (i.lower(), i.upper()))
+                .collect();
+            assert_eq!(windows, expected, "test #{} of windows", id);
+        }
+        let max = <i8 as Width>::max_value();
+        let min = <i8 as Width>::min_value();
+        let whole = IntervalSet::<i8>::whole();
+        assert_eq!(
+            whole.windows(whole.size()).collect::<Vec<_>>(),
+            vec![Interval::new(min, max)]
+        );
+        assert_eq!(whole.windows(254).count(), 2);
+    }
+
+    #[test]
+    fn test_ascii_bar() {
+        let interval_set = make_interval_set(vec![(1, 3), (6, 6)]);
+        assert_eq!(interval_set.ascii_bar(0, 7), ".XXX..X.");
+        assert_eq!(interval_set.ascii_bar(2, 2), "X");
+        assert_eq!(interval_set.ascii_bar(4, 5), "..");
+        assert_eq!(interval_set.ascii_bar(-2, 1), "...X");
+        assert_eq!(IntervalSet::<i32>::empty().ascii_bar(0, 3), "....");
+        let max = <u8 as Width>::max_value();
+        let edge = IntervalSet::new(max - 1, max);
+        assert_eq!(edge.ascii_bar(max - 2, max), ".XX");
+        let whole = IntervalSet::<i32>::whole();
+        assert_eq!(
+            whole
+                .ascii_bar(<i32 as Width>::min_value(), <i32 as Width>::max_value())
+                .len(),
+            ASCII_BAR_MAX_WIDTH
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ascii_bar_empty_window() {
+        IntervalSet::new(0, 1).ascii_bar(1, 0);
+    }
+
+    #[test]
+    fn test_to_coverage_vec() {
+        let interval_set = make_interval_set(vec![(-3, 1), (4, 5), (8, 12)]);
+        let cases = vec![
+            (1, -1, 9, "XXX..XX..XX"),
+            (2, -5, -2, "..XX"),
+            (3, 10, 15, "XXX..."),
+            (4, 2, 3, ".."),
+            (5, 9, 9, "X"),
+            (6, -10, 20, ".......XXXXX..XX..XXXXX........"),
+            (7, 3, 2, ""),
+        ];
+        for (id, lb, ub, expected) in cases {
+            let expected: Vec<bool> = expected.chars().map(|c| c == 'X').collect();
+            assert_eq!(
+                interval_set.to_coverage_vec(lb, ub),
+                expected,
+                "test #{} of to_coverage_vec",
+                id
+            );
+            let contained: Vec<bool> = (lb..=ub).map(|v| interval_set.contains(&v)).collect();
+            assert_eq!(contained, expected, "test #{} of to_coverage_vec", id);
+        }
+        assert_eq!(
+            IntervalSet::<i32>::empty().to_coverage_vec(0, 2),
+            vec![false; 3]
+        );
+        let max = <u8 as Width>::max_value();
+        let edge = IntervalSet::new(max - 1, max);
+        assert_eq!(edge.to_coverage_vec(max - 2, max), vec![false, true, true]);
     }
 
     #[test]
-    fn test_contains() {
+    fn test_expand_to_multiple() {
         let cases = vec![
-            (vec![], vec![], vec![-2, -1, 0, 1, 2]),
-            (vec![(1, 2)], vec![1, 2], vec![-1, 0, 3, 4]),
-            (
-                vec![(1, 2), (7, 9)],
-                vec![1, 2, 7, 8, 9],
-                vec![-1, 0, 3, 4, 5, 6, 10, 11],
-            ),
-            (
-                vec![(1, 2), (4, 5), (7, 9)],
-                vec![1, 2, 4, 5, 7, 8, 9],
-                vec![-1, 0, 3, 6, 10, 11],
-            ),
+            (1, vec![], 4, vec![]),
+            (2, vec![(3, 5), (9, 10)], 4, vec![(0, 11)]),
+            (3, vec![(3, 5), (13, 13)], 4, vec![(0, 7), (12, 15)]),
+            (4, vec![(0, 3), (8, 11)], 4, vec![(0, 3), (8, 11)]),
+            (5, vec![(-3, -1), (5, 5)], 4, vec![(-4, -1), (4, 7)]),
+            (6, vec![(-5, -5), (-1, 0)], 4, vec![(-8, 3)]),
+            (7, vec![(-9, 7), (20, 25)], 1, vec![(-9, 7), (20, 25)]),
         ];
 
-        for (is, inside, outside) in cases {
-            let is = make_interval_set(is);
-            test_inside_outside(is, inside, outside);
+        for (id, a, m, expected) in cases {
+            test_op(
+                format!("test #{} of expand_to_multiple", id),
+                a,
+                |x| x.expand_to_multiple(m),
+                expected,
+            );
         }
     }
 
     #[test]
-    fn test_complement() {
-        let min = <i32 as Width>::min_value();
-        let max = <i32 as Width>::max_value();
+    #[should_panic]
+    fn test_expand_to_multiple_zero() {
+        IntervalSet::new(1, 2).expand_to_multiple(0);
+    }
 
+    #[test]
+    fn test_rotate() {
         let cases = vec![
-            (1, vec![], vec![(min, max)]),
-            (2, vec![(min, max)], vec![]),
-            (3, vec![(0, 0)], vec![(min, -1), (1, max)]),
-            (4, vec![(-5, 5)], vec![(min, -6), (6, max)]),
-            (5, vec![(-5, -1), (1, 5)], vec![(min, -6), (0, 0), (6, max)]),
-            (6, vec![(min, -1), (1, 5)], vec![(0, 0), (6, max)]),
-            (7, vec![(-5, -1), (1, max)], vec![(min, -6), (0, 0)]),
-            (8, vec![(min, -1), (1, max)], vec![(0, 0)]),
-            (
-                9,
-                vec![(-5, -3), (0, 1), (3, 5)],
-                vec![(min, -6), (-2, -1), (2, 2), (6, max)],
-            ),
+            (1, vec![], 3, 6, vec![]),
+            (2, vec![(2, 4)], 3, 6, vec![(0, 1), (5, 5)]),
+            (3, vec![(2, 4)], 0, 6, vec![(2, 4)]),
+            (4, vec![(2, 4)], 9, 6, vec![(0, 1), (5, 5)]),
+            (5, vec![(2, 4)], -2, 6, vec![(0, 2)]),
+            (6, vec![(0, 0), (5, 5)], 1, 6, vec![(0, 1)]),
+            (7, vec![(4, 7)], 1, 6, vec![(0, 2), (5, 5)]),
+            (8, vec![(4, 8)], 0, 6, vec![(0, 2), (4, 5)]),
+            (9, vec![(4, 9)], 2, 6, vec![(0, 5)]),
+            (10, vec![(-20, 20)], 3, 6, vec![(0, 5)]),
+            (11, vec![(-3, -2), (7, 7)], 0, 6, vec![(1, 1), (3, 4)]),
+            (12, vec![(1, 1), (3, 3)], 5, 6, vec![(0, 0), (2, 2)]),
+            (13, vec![(-7, 3), (12, 15)], 4, 1, vec![(0, 0)]),
         ];
 
-        for (id, a, expected) in cases {
-            test_op(
-                format!("test #{} of complement", id),
-                a.clone(),
-                |x| x.complement(),
-                expected,
-            );
+        for (id, a, shift, modulus, expected) in cases {
             test_op(
-                format!("test #{} of complement(complement)", id),
-                a.clone(),
-                |x| x.complement().complement(),
+                format!("test #{} of rotate", id),
                 a,
+                |x| x.rotate(shift, modulus),
+                expected,
             );
         }
+        let max = <i32 as Width>::max_value();
+        assert_eq!(
+            IntervalSet::new(max - 2, max - 1).rotate(max - 1, max),
+            IntervalSet::new(max - 3, max - 2)
+        );
     }
 
     #[test]
-    fn test_union() {
-        // Note: the first number is the test id, so it should be easy to identify which test has failed.
-        // The two first vectors are the operands and the expected result is last.
-        let sym_cases = vec![
-            // identity tests
-            (1, vec![], vec![], vec![]),
-            (2, vec![], vec![(1, 2)], vec![(1, 2)]),
-            (3, vec![], vec![(1, 2), (7, 9)], vec![(1, 2), (7, 9)]),
-            (4, vec![(1, 2), (7, 9)], vec![(1, 2)], vec![(1, 2), (7, 9)]),
-            (
-                5,
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
-            ),
-            // front tests
-            (
-                6,
-                vec![(-3, -1)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, -1), (1, 2), (7, 9)],
-            ),
-            (
-                7,
-                vec![(-3, 0)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 2), (7, 9)],
-            ),
-            (
-                8,
-                vec![(-3, 1)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 2), (7, 9)],
-            ),
-            // middle tests
-            (9, vec![(2, 7)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
-            (10, vec![(3, 7)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
-            (
-                11,
-                vec![(4, 5)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (4, 5), (7, 9)],
-            ),
-            (12, vec![(2, 8)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
-            (13, vec![(2, 6)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
-            (14, vec![(3, 6)], vec![(1, 2), (7, 9)], vec![(1, 9)]),
-            // back tests
-            (15, vec![(8, 9)], vec![(1, 2), (7, 9)], vec![(1, 2), (7, 9)]),
-            (
-                16,
-                vec![(8, 10)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 10)],
-            ),
-            (
-                17,
-                vec![(9, 10)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 10)],
-            ),
-            (
-                18,
-                vec![(6, 10)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (6, 10)],
-            ),
-            (
-                19,
-                vec![(10, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 11)],
-            ),
-            (
-                20,
-                vec![(11, 12)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9), (11, 12)],
-            ),
-            // mixed tests
-            (
-                21,
-                vec![(-3, -1), (4, 5), (11, 12)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, -1), (1, 2), (4, 5), (7, 9), (11, 12)],
-            ),
-            (
-                22,
-                vec![(-3, 0), (3, 6), (10, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 11)],
-            ),
-            (
-                23,
-                vec![(-3, 1), (3, 7), (9, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 11)],
-            ),
-            (
-                24,
-                vec![(-3, 5), (7, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 5), (7, 11)],
-            ),
-            (
-                25,
-                vec![(-3, 5), (7, 8), (12, 12)],
-                vec![(1, 2), (7, 9)],
-                vec![(-3, 5), (7, 9), (12, 12)],
-            ),
-            // englobing tests
-            (26, vec![(-1, 11)], vec![(1, 2), (7, 9)], vec![(-1, 11)]),
+    #[should_panic]
+    fn test_rotate_zero_modulus() {
+        IntervalSet::new(1, 2).rotate(1, 0);
+    }
+
+    #[test]
+    fn test_compact() {
+        let sets = vec![
+            vec![],
+            vec![(5, 5)],
+            vec![(10, 12), (20, 21), (30, 30)],
+            vec![(-8, -6), (-2, 3), (9, 9), (12, 15)],
         ];
+        for a in sets {
+            let a = make_interval_set(a);
+            let (compact, mapping) = a.compact();
+            assert_eq!(compact.size(), a.size());
+            assert_eq!(mapping.len(), a.interval_count());
+            if !a.is_empty() {
+                assert_eq!(compact, IntervalSet::new(0, a.size() as i32 - 1));
+            }
+            for (rank, v) in a.iter_values().enumerate() {
+                let &(lower, position) =
+                    mapping.iter().rev().find(|(lower, _)| *lower <= v).unwrap();
+                assert_eq!(position + (v - lower), rank as i32, "{} in {}", v, a);
+            }
+        }
+    }
 
-        for (id, a, b, expected) in sym_cases {
-            test_binary_op_sym(
-                format!("test #{} of union", id),
-                a,
-                b,
-                |x, y| x.union(y),
-                expected,
-            );
+    #[test]
+    fn test_sum_of_values() {
+        let sets = vec![
+            vec![],
+            vec![(1, 3), (10, 10)],
+            vec![(-4, 2)],
+            vec![(-9, -7), (-1, 0), (5, 11)],
+            vec![(0, 0)],
+        ];
+        for a in sets {
+            let a = make_interval_set(a);
+            let expected: i128 = a.iter_values().map(|v| v as i128).sum();
+            assert_eq!(a.sum_of_values(), expected, "{}", a);
         }
+        let max = <u64 as Width>::max_value() as i128;
+        assert_eq!(
+            IntervalSet::<u64>::whole().sum_of_values(),
+            max / 2 * (max + 1)
+        );
+        assert_eq!(IntervalSet::<i64>::whole().sum_of_values(), 0);
     }
 
     #[test]
-    fn test_intersection() {
-        // Note: the first number is the test id, so it should be easy to identify which test has failed.
-        // The two first vectors are the operands and the expected result is last.
-        let sym_cases = vec![
-            // identity tests
-            (1, vec![], vec![], vec![]),
-            (2, vec![], vec![(1, 2)], vec![]),
-            (3, vec![], vec![(1, 2), (7, 9)], vec![]),
-            (4, vec![(1, 2), (7, 9)], vec![(1, 2)], vec![(1, 2)]),
-            (
-                5,
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
-            ),
-            // front tests
-            (6, vec![(-3, -1)], vec![(1, 2), (7, 9)], vec![]),
-            (7, vec![(-3, 0)], vec![(1, 2), (7, 9)], vec![]),
-            (8, vec![(-3, 1)], vec![(1, 2), (7, 9)], vec![(1, 1)]),
-            // middle tests
-            (9, vec![(2, 7)], vec![(1, 2), (7, 9)], vec![(2, 2), (7, 7)]),
-            (10, vec![(3, 7)], vec![(1, 2), (7, 9)], vec![(7, 7)]),
-            (11, vec![(4, 5)], vec![(1, 2), (7, 9)], vec![]),
-            (12, vec![(2, 8)], vec![(1, 2), (7, 9)], vec![(2, 2), (7, 8)]),
-            (13, vec![(2, 6)], vec![(1, 2), (7, 9)], vec![(2, 2)]),
-            (14, vec![(3, 6)], vec![(1, 2), (7, 9)], vec![]),
-            // back tests
-            (15, vec![(8, 9)], vec![(1, 2), (7, 9)], vec![(8, 9)]),
-            (16, vec![(8, 10)], vec![(1, 2), (7, 9)], vec![(8, 9)]),
-            (17, vec![(9, 10)], vec![(1, 2), (7, 9)], vec![(9, 9)]),
-            (18, vec![(6, 10)], vec![(1, 2), (7, 9)], vec![(7, 9)]),
-            (19, vec![(10, 11)], vec![(1, 2), (7, 9)], vec![]),
-            (20, vec![(11, 12)], vec![(1, 2), (7, 9)], vec![]),
-            // mixed tests
-            (
-                21,
-                vec![(-3, -1), (4, 5), (11, 12)],
-                vec![(1, 2), (7, 9)],
-                vec![],
-            ),
-            (
-                22,
-                vec![(-3, 0), (3, 6), (10, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![],
-            ),
-            (
-                23,
-                vec![(-3, 1), (3, 7), (9, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 1), (7, 7), (9, 9)],
-            ),
+    fn test_detect_step() {
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let cases = vec![
+            (1, vec![], None),
+            (2, vec![(4, 4)], None),
+            (3, vec![(0, 0), (3, 3), (6, 6), (9, 9)], Some(3)),
+            (4, vec![(0, 0), (3, 3), (5, 5)], None),
+            (5, vec![(-7, -7), (3, 3)], Some(10)),
+            (6, vec![(0, 0), (2, 2), (4, 5)], None),
+            (7, vec![(0, 2)], None),
+            (8, vec![(-4, -4), (-2, -2), (0, 0), (2, 2)], Some(2)),
             (
-                24,
-                vec![(-3, 5), (7, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
+                9,
+                vec![
+                    (-2_000_000_000, -2_000_000_000),
+                    (2_000_000_000, 2_000_000_000),
+                ],
+                None,
             ),
+            (10, vec![(min, min), (0, 0), (max, max)], Some(max)),
+        ];
+        for (id, a, expected) in cases {
+            assert_eq!(
+                make_interval_set(a).detect_step(),
+                expected,
+                "test #{} of detect_step",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_as_arithmetic_progression() {
+        let cases = vec![
+            (1, vec![], None),
+            (2, vec![(4, 4)], Some((4, 0, 1))),
+            (3, vec![(0, 0), (3, 3), (6, 6), (9, 9)], Some((0, 3, 4))),
+            (4, vec![(0, 0), (3, 3), (6, 6), (8, 8)], None),
+            (5, vec![(-7, -7), (3, 3)], Some((-7, 10, 2))),
+            (6, vec![(0, 1)], None),
+            (7, vec![(0, 0), (2, 2), (4, 5)], None),
             (
-                25,
-                vec![(-3, 5), (7, 8), (12, 12)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 8)],
+                8,
+                vec![
+                    (-2_000_000_000, -2_000_000_000),
+                    (2_000_000_000, 2_000_000_000),
+                ],
+                None,
             ),
-            // englobing tests
             (
-                26,
-                vec![(-1, 11)],
-                vec![(1, 2), (7, 9)],
-                vec![(1, 2), (7, 9)],
+                9,
+                vec![
+                    (-1_000_000_000, -1_000_000_000),
+                    (0, 0),
+                    (1_000_000_000, 1_000_000_000),
+                ],
+                Some((-1_000_000_000, 1_000_000_000, 3)),
             ),
         ];
+        for (id, a, expected) in cases {
+            let a = make_interval_set(a);
+            let progression = a.as_arithmetic_progression();
+            assert_eq!(
+                progression, expected,
+                "test #{} of as_arithmetic_progression",
+                id
+            );
+            if let Some((start, step, count)) = progression {
+                let values: Vec<i32> = (0..count as i32).map(|k| start + k * step).collect();
+                assert_eq!(
+                    values,
+                    a.iter_values().collect::<Vec<_>>(),
+                    "test #{} of as_arithmetic_progression",
+                    id
+                );
+            }
+        }
+    }
 
-        for (id, a, b, expected) in sym_cases {
-            test_binary_op_sym(
-                format!("test #{} of intersection", id),
-                a,
-                b,
-                |x, y| x.intersection(y),
-                expected,
+    #[test]
+    fn test_span_and_gap_sizes() {
+        let cases = vec![
+            (1, vec![], 0, 0),
+            (2, vec![(5, 5)], 1, 0),
+            (3, vec![(-3, 4)], 8, 0),
+            (4, vec![(0, 0), (9, 9)], 10, 8),
+            (5, vec![(-5, -3), (0, 1), (5, 7)], 13, 5),
+        ];
+        for (id, a, span_size, gap_size) in cases {
+            let a = make_interval_set(a);
+            assert_eq!(a.span_size(), span_size, "test #{} of span_size", id);
+            assert_eq!(
+                a.total_gap_size(),
+                gap_size,
+                "test #{} of total_gap_size",
+                id
             );
         }
+        let min = <i32 as Width>::min_value();
+        let max = <i32 as Width>::max_value();
+        let extremes = make_interval_set(vec![(min, min), (max, max)]);
+        assert_eq!(extremes.span_size(), IntervalSet::<i32>::whole().size());
+        assert_eq!(extremes.total_gap_size(), extremes.span_size() - 2);
     }
 
     #[test]
-    fn test_to_interval_set() {
-        // This example should not panic, and should yield the correct result.
-        let intervals = vec![(3, 8), (2, 5)].to_interval_set();
-        assert_eq!(intervals.interval_count(), 1);
-        assert_eq!(intervals.lower(), 2);
-        assert_eq!(intervals.upper(), 8);
+    fn test_first_gap_of_size() {
+        let interval_set = vec![(0, 1), (5, 5), (10, 20)];
+        let cases = vec![
+            (1, vec![], 1, None),
+            (2, vec![(0, 9)], 1, None),
+            (3, interval_set.clone(), 0, Some((2, 4))),
+            (4, interval_set.clone(), 2, Some((2, 4))),
+            (5, interval_set.clone(), 3, Some((2, 4))),
+            (6, interval_set.clone(), 4, Some((6, 9))),
+            (7, interval_set.clone(), 5, None),
+            (8, vec![(-8, -6), (-2, 0), (2, 2)], 1, Some((-5, -3))),
+        ];
+        for (id, a, size, expected) in cases {
+            assert_eq!(
+                make_interval_set(a).first_gap_of_size(size),
+                expected.map(|(l, u)| Interval::new(l, u)),
+                "test #{} of first_gap_of_size",
+                id
+            );
+        }
+
+        let cases = vec![
+            (1, vec![], 3, (0, 9), Some((0, 9))),
+            (2, vec![], 11, (0, 9), None),
+            (3, interval_set.clone(), 2, (0, 30), Some((2, 4))),
+            (4, interval_set.clone(), 2, (-10, 30), Some((-10, -1))),
+            (5, interval_set.clone(), 5, (0, 30), Some((21, 30))),
+            (6, interval_set.clone(), 5, (0, 20), None),
+            (7, interval_set.clone(), 3, (3, 15), Some((6, 9))),
+            (8, interval_set.clone(), 5, (3, 15), None),
+            (9, interval_set, 2, (3, 15), Some((3, 4))),
+            (10, vec![(0, 9)], 1, (2, 5), None),
+        ];
+        for (id, a, size, (lb, ub), expected) in cases {
+            assert_eq!(
+                make_interval_set(a).first_gap_of_size_within(size, lb, ub),
+                expected.map(|(l, u)| Interval::new(l, u)),
+                "test #{} of first_gap_of_size_within",
+                id
+            );
+        }
     }
 
     #[test]
-    #[should_panic(
-        expected = "This operation is only for pushing interval to the back of the array, possibly overlapping with the last element."
-    )]
-    fn test_extend_back() {
-        // Calling extend_at_back with unordered input should panic.
-        let mut set = IntervalSet::empty();
-        let intervals = extend_example.map(|i| i.to_interval());
-        set.extend_at_back(intervals);
-        assert_eq!(set.interval_count(), 2);
+    fn test_count_small_intervals() {
+        let interval_set =
+            make_interval_set(vec![(-3, -3), (0, 1), (5, 7), (10, 13), (20, 20), (30, 59)]);
+        let cases = vec![
+            (0, 0),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (29, 5),
+            (30, 6),
+            (100, 6),
+        ];
+        for (max_size, expected) in cases {
+            assert_eq!(
+                interval_set.count_small_intervals(max_size),
+                expected,
+                "max_size = {}",
+                max_size
+            );
+        }
+        assert_eq!(IntervalSet::<i32>::empty().count_small_intervals(10), 0);
+        let whole = IntervalSet::<u8>::whole();
+        assert_eq!(whole.count_small_intervals(whole.size()), 1);
+        assert_eq!(whole.count_small_intervals(whole.size() - 1), 0);
     }
 
     #[test]
-    fn test_extend_empty() {
-        // Calling extend with unordered input should not panic.
-        let mut set = IntervalSet::empty();
-        let intervals = extend_example.map(|i| i.to_interval());
-        set.extend(intervals);
-        assert_eq!(set.interval_count(), 2);
+    fn test_size_histogram() {
+        let cases = vec![
+            (1, vec![], vec![]),
+            (
+                2,
+                vec![(0, 0), (2, 4), (10, 17), (20, 28)],
+                vec![(0, 1), (1, 1), (3, 2)],
+            ),
+            (
+                3,
+                vec![(-3, -3), (0, 1), (5, 7), (10, 13)],
+                vec![(0, 1), (1, 2), (2, 1)],
+            ),
+        ];
+        for (id, a, expected) in cases {
+            let expected: BTreeMap<u32, usize> = expected.into_iter().collect();
+            assert_eq!(
+                make_interval_set(a).size_histogram(),
+                expected,
+                "test #{} of size_histogram",
+                id
+            );
+        }
+        let whole = IntervalSet::<u8>::whole().size_histogram();
+        assert_eq!(whole, BTreeMap::from([(7, 1)]));
     }
 
     #[test]
-    fn test_extend_non_empty() {
-        // Extending an IntervalSet with intervals that belong at the start or
-        // the middle of the set should not panic.
-        let mut intervals = vec![(10, 15), (20, 30)].to_interval_set();
-        let at_start = vec![(0, 5).to_interval()];
-        intervals.extend(at_start);
-        let in_middle = vec![(17, 18).to_interval()];
-        intervals.extend(in_middle);
+    fn test_bounding_bits() {
+        let cases = vec![
+            (1, vec![], None),
+            (2, vec![(0, 0)], Some(1)),
+            (3, vec![(0, 1)], Some(1)),
+            (4, vec![(0, 255)], Some(8)),
+            (5, vec![(3, 5), (200, 256)], Some(9)),
+            (6, vec![(-1, -1)], Some(1)),
+            (7, vec![(-1, 1)], Some(2)),
+            (8, vec![(-2, 1)], Some(2)),
+            (9, vec![(-128, 127)], Some(8)),
+            (10, vec![(-129, -100)], Some(9)),
+            (11, vec![(-5, -3), (128, 128)], Some(9)),
+        ];
 
-        assert_eq!(intervals.interval_count(), 4);
-        assert_eq!(intervals.lower(), 0);
-        assert_eq!(intervals.upper(), 30);
+        for (id, a, expected) in cases {
+            println!("Info: test #{} of bounding_bits.", id);
+            assert_eq!(make_interval_set(a).bounding_bits(), expected);
+        }
+
+        assert_eq!(IntervalSet::<u8>::whole().bounding_bits(), Some(8));
+        assert_eq!(IntervalSet::<i8>::whole().bounding_bits(), Some(8));
+        assert_eq!(IntervalSet::<i64>::whole().bounding_bits(), Some(64));
     }
 
     #[test]
@@ -2095,6 +8082,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_difference_small_rhs() {
+        let a: IntervalSet<i32> = (0..1000)
+            .map(|i| (4 * i, 4 * i + 2))
+            .collect::<Vec<_>>()
+            .to_interval_set();
+        let b = vec![(-10, 1), (1001, 2000), (3998, 5000)].to_interval_set();
+        let result = a.difference(&b);
+        assert_eq!(result, a.intersection(&b.complement()));
+        // Intervals strictly inside `(1001, 2000)` are removed, the others are cut or kept.
+        assert_eq!(result.interval_count(), 1000 - 249);
+        assert_eq!(result.lower(), 2);
+        assert_eq!(result.upper(), 3997);
+    }
+
+    #[test]
+    fn test_difference_intervals() {
+        let interval_set = make_interval_set(vec![(0, 100)]);
+        let holes = [
+            Interval::new(10, 19),
+            Interval::new(40, 45),
+            Interval::new(90, 100),
+        ];
+        let expected = make_interval_set(vec![(0, 9), (20, 39), (46, 89)]);
+        assert_eq!(interval_set.difference_intervals(&holes), expected);
+
+        let sets = vec![
+            vec![],
+            vec![(0, 100)],
+            vec![(-20, -10), (0, 5), (8, 30), (40, 41)],
+        ];
+        let hole_lists = vec![
+            vec![],
+            vec![(-15, 2)],
+            vec![(-30, -25), (-12, 3), (-11, -11), (4, 9), (20, 22), (21, 40)],
+            vec![(0, 50), (2, 3), (10, 60)],
+            vec![(-100, 100)],
+        ];
+        for a in &sets {
+            let a = make_interval_set(a.clone());
+            for holes in &hole_lists {
+                let holes: Vec<_> = holes.iter().map(|&(l, u)| Interval::new(l, u)).collect();
+                let expected = holes.iter().fold(a.clone(), |acc, h| {
+                    acc.difference(&IntervalSet::from_interval(*h))
+                });
+                assert_eq!(
+                    a.difference_intervals(&holes),
+                    expected,
+                    "{} minus {:?}",
+                    a,
+                    holes
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert_within() {
+        let cases = vec![
+            (1, vec![], vec![], vec![]),
+            (2, vec![], vec![(0, 20)], vec![(0, 20)]),
+            (3, vec![(0, 20)], vec![], vec![]),
+            (
+                4,
+                vec![(5, 6), (10, 12)],
+                vec![(0, 20)],
+                vec![(0, 4), (7, 9), (13, 20)],
+            ),
+            (
+                5,
+                vec![(-5, 6), (10, 12)],
+                vec![(0, 8), (11, 20)],
+                vec![(7, 8), (13, 20)],
+            ),
+        ];
+
+        for (id, a, universe, expected) in cases {
+            test_binary_op(
+                format!("test #{} of invert_within", id),
+                a,
+                universe,
+                |x, y| x.invert_within(y),
+                expected,
+            );
+        }
+    }
+
     #[test]
     fn test_symmetric_difference() {
         // Note: the first number is the test id, so it should be easy to identify which test has failed.
@@ -2307,6 +8381,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shrink_opt() {
+        let sets = vec![vec![], vec![(-5, 5)], vec![(-5, -1), (1, 5)]];
+        for a in sets {
+            let a = make_interval_set(a);
+            assert_eq!(a.shrink_left_opt(Optional::empty()), a);
+            assert_eq!(a.shrink_right_opt(Optional::empty()), a);
+            for v in -6..=6 {
+                assert_eq!(a.shrink_left_opt(Optional::singleton(v)), a.shrink_left(v));
+                assert_eq!(
+                    a.shrink_right_opt(Optional::singleton(v)),
+                    a.shrink_right(v)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlap_far_apart_skips_sweep() {
+        let low = make_interval_set((0..100).map(|i| (i * 3, i * 3 + 1)).collect());
+        let high = make_interval_set((0..100).map(|i| (1000 + i * 3, 1000 + i * 3 + 1)).collect());
+        OVERLAP_SWEEP_STEPS.with(|steps| steps.set(0));
+        assert!(!low.overlap(&high));
+        assert!(!high.overlap(&low));
+        assert!(!low.overlap(&IntervalSet::empty()));
+        assert_eq!(OVERLAP_SWEEP_STEPS.with(|steps| steps.get()), 0);
+        assert!(low.overlap(&low));
+        assert!(OVERLAP_SWEEP_STEPS.with(|steps| steps.get()) > 0);
+    }
+
+    #[test]
+    fn test_overlap_count() {
+        let sym_cases = vec![
+            (1, vec![], vec![], 0),
+            (2, vec![], vec![(1, 2)], 0),
+            (3, vec![(0, 10)], vec![(1, 2), (4, 5), (8, 9)], 3),
+            (4, vec![(0, 10)], vec![(-5, 0), (10, 15)], 2),
+            (5, vec![(1, 2), (7, 9)], vec![(3, 6)], 0),
+            (6, vec![(1, 2), (7, 9)], vec![(2, 7)], 2),
+            (7, vec![(0, 4), (6, 10)], vec![(1, 1), (3, 7), (9, 12)], 4),
+        ];
+
+        for (id, a, b, expected) in sym_cases {
+            println!("Info: test #{} of overlap_count.", id);
+            let a = make_interval_set(a);
+            let b = make_interval_set(b);
+            assert_eq!(a.overlap_count(&b), expected);
+            assert_eq!(b.overlap_count(&a), expected);
+        }
+    }
+
     fn overlap_cases() -> Vec<(u32, Vec<(i32, i32)>, i32, bool)> {
         vec![
             (1, vec![], 0, false),
@@ -2436,6 +8561,24 @@ mod tests {
                 vec![(1, 1), (3, 5)],
                 vec![(-5, -3), (0, 1)],
             ),
+            // The value lies in a gap: on its first value, in its middle and on its last value.
+            (17, vec![(1, 3), (7, 9)], 4, vec![(7, 9)], vec![(1, 3)]),
+            (18, vec![(1, 3), (7, 9)], 5, vec![(7, 9)], vec![(1, 3)]),
+            (19, vec![(1, 3), (7, 9)], 6, vec![(7, 9)], vec![(1, 3)]),
+            (
+                20,
+                vec![(1, 3), (7, 9), (12, 12)],
+                10,
+                vec![(12, 12)],
+                vec![(1, 3), (7, 9)],
+            ),
+            (
+                21,
+                vec![(1, 3), (7, 9), (12, 12)],
+                11,
+                vec![(12, 12)],
+                vec![(1, 3), (7, 9)],
+            ),
         ];
 
         for (id, a, v, expected_left, expected_right) in cases {
@@ -2456,6 +8599,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shrink_cow() {
+        let cases = vec![
+            (1, vec![], 0, true, true),
+            (2, vec![(-5, 5)], 0, false, false),
+            (3, vec![(-5, 5)], -5, true, false),
+            (4, vec![(-5, 5)], 5, false, true),
+            (5, vec![(-5, -1), (1, 5)], -6, true, false),
+            (6, vec![(-5, -1), (1, 5)], 6, false, true),
+        ];
+
+        for (id, a, v, borrowed_left, borrowed_right) in cases {
+            println!(
+                "Info: test #{} of shrink_left_cow and shrink_right_cow.",
+                id
+            );
+            let a = make_interval_set(a);
+            let left = a.shrink_left_cow(v);
+            assert_eq!(matches!(left, Cow::Borrowed(_)), borrowed_left);
+            assert_eq!(left.into_owned(), a.shrink_left(v));
+            let right = a.shrink_right_cow(v);
+            assert_eq!(matches!(right, Cow::Borrowed(_)), borrowed_right);
+            assert_eq!(right.into_owned(), a.shrink_right(v));
+        }
+    }
+
     #[test]
     fn test_subset() {
         // Note: the first number is the test id, so it should be easy to identify which test has failed.
@@ -2559,6 +8728,61 @@ mod tests {
                 false,
                 true,
             ),
+            // nested tests
+            (
+                14,
+                vec![(1, 2), (4, 5), (7, 8)],
+                vec![(0, 10)],
+                true,
+                false,
+                true,
+                false,
+            ),
+            (
+                15,
+                vec![(1, 2), (4, 5), (7, 8), (12, 12), (14, 15)],
+                vec![(0, 10), (12, 20)],
+                true,
+                false,
+                true,
+                false,
+            ),
+            (
+                16,
+                vec![(1, 2), (4, 5), (9, 12)],
+                vec![(0, 10), (12, 20)],
+                false,
+                false,
+                false,
+                false,
+            ),
+            (
+                17,
+                vec![(0, 2), (4, 5), (7, 10)],
+                vec![(0, 10)],
+                true,
+                false,
+                true,
+                false,
+            ),
+            (
+                18,
+                vec![(0, 2), (4, 5), (8, 11)],
+                vec![(0, 10), (12, 20)],
+                false,
+                false,
+                false,
+                false,
+            ),
+            (
+                19,
+                vec![(1, 2), (4, 5), (14, 15), (17, 18)],
+                vec![(0, 5), (7, 9), (11, 20)],
+                true,
+                false,
+                true,
+                false,
+            ),
         ];
 
         for (id, a, b, expected, expected_sym, expected_proper, expected_proper_sym) in cases {
@@ -2669,6 +8893,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_arithmetics_interval() {
+        let sets = vec![
+            vec![],
+            vec![(0, 0)],
+            vec![(1, 1), (3, 5)],
+            vec![(-10, -7), (-2, 3), (8, 8), (12, 20)],
+        ];
+        let intervals = vec![
+            Interval::empty(),
+            Interval::new(0, 0),
+            Interval::new(1, 1),
+            Interval::new(-3, -3),
+            Interval::new(0, 2),
+            Interval::new(-4, 3),
+            Interval::new(-6, -2),
+        ];
+        for a in sets {
+            let a = make_interval_set(a);
+            for i in &intervals {
+                let b = IntervalSet::from_interval(*i);
+                assert_eq!(&a + i, &a + &b, "{:?} + {:?}", a, i);
+                assert_eq!(&a - i, &a - &b, "{:?} - {:?}", a, i);
+                assert_eq!(&a * i, &a * &b, "{:?} * {:?}", a, i);
+                assert_eq!((&a * i).validate(), Ok(()), "{:?} * {:?}", a, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_negative_constant() {
+        let sets = vec![
+            vec![(1, 2), (5, 6)],
+            vec![(-10, -7), (-2, 3), (8, 8), (12, 20)],
+            vec![(0, 0)],
+        ];
+        for a in sets {
+            let a = make_interval_set(a);
+            for k in [-1, -2, -3, -7] {
+                let res = &a * k;
+                assert_eq!(res.validate(), Ok(()), "{:?} * {}", a, k);
+                assert_eq!(res, &a * &IntervalSet::singleton(k), "{:?} * {}", a, k);
+                assert_eq!(res, (&a * -k) * -1, "{:?} * {}", a, k);
+            }
+        }
+        let a = make_interval_set(vec![(1, 2), (5, 6)]);
+        assert_eq!(&a * -2, make_interval_set(vec![(-12, -10), (-4, -2)]));
+        assert_eq!(&a * -1, make_interval_set(vec![(-6, -5), (-2, -1)]));
+    }
+
     #[test]
     fn test_arithmetics_bound() {
         let i1_35 = vec![(1, 1), (3, 5)];
@@ -2719,6 +8993,22 @@ mod tests {
                 vec![(-2, -2), (0, 2)],
                 vec![(3, 3), (9, 15)],
             ),
+            (
+                10,
+                i1_35.clone(),
+                -1,
+                vec![(0, 0), (2, 4)],
+                vec![(2, 2), (4, 6)],
+                vec![(-5, -3), (-1, -1)],
+            ),
+            (
+                11,
+                vec![(1, 2), (5, 6)],
+                -2,
+                vec![(-1, 0), (3, 4)],
+                vec![(3, 4), (7, 8)],
+                vec![(-12, -10), (-4, -2)],
+            ),
         ];
 
         for (id, a, b, e_add, e_sub, e_mul) in cases {
@@ -2746,6 +9036,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_entail_single_subset_test() {
+        use trilean::SKleene::*;
+        let sets = vec![
+            vec![],
+            vec![(0, 5), (10, 15)],
+            vec![(5, 10)],
+            vec![(6, 9)],
+            vec![(0, 15)],
+            vec![(0, 1), (4, 5)],
+        ];
+        for a in &sets {
+            for b in &sets {
+                let a = make_interval_set(a.clone());
+                let b = make_interval_set(b.clone());
+                let expected = if a.is_subset(&b) {
+                    True
+                } else if b.is_subset(&a) {
+                    False
+                } else {
+                    Unknown
+                };
+                IS_SUBSET_CALLS.with(|calls| calls.set(0));
+                assert_eq!(a.entail(&b), expected, "{} |= {}", a, b);
+                assert_eq!(IS_SUBSET_CALLS.with(|calls| calls.get()), 1);
+            }
+        }
+    }
+
     #[test]
     fn test_lattice() {
         use gcollections::ops::lattice::test::*;
@@ -2803,6 +9122,20 @@ mod tests {
         tester.test_all();
     }
 
+    #[test]
+    fn test_sum_product() {
+        let a = vec![(0, 5), (10, 15)].to_interval_set();
+        let b = vec![(5, 10)].to_interval_set();
+        let c = vec![(4, 6), (8, 12), (20, 21)].to_interval_set();
+        let sets = vec![a.clone(), b.clone(), c.clone()];
+
+        let sum = sets.clone().into_iter().sum::<IntervalSet<_>>();
+        assert_eq!(sum, a.union(&b).union(&c));
+        let product = sets.into_iter().product::<IntervalSet<_>>();
+        assert_eq!(product, a.intersection(&b).intersection(&c));
+        assert_eq!(product, vec![(5, 5), (10, 10)].to_interval_set());
+    }
+
     #[test]
     fn test_iterator() {
         let empty = IntervalSet::<i32>::empty();
@@ -2872,4 +9205,64 @@ mod tests {
     fn test_ser_de_empty_interval_set() {
         assert_tokens(&IntervalSet::<i32>::empty(), &[Token::None]);
     }
+
+    fn interval_tokens(intervals: &[(i32, i32)]) -> Vec<Token> {
+        let mut tokens = vec![Token::Seq {
+            len: Some(intervals.len()),
+        }];
+        for &(l, u) in intervals {
+            tokens.extend([
+                Token::Tuple { len: 2 },
+                Token::I32(l),
+                Token::I32(u),
+                Token::TupleEnd,
+            ]);
+        }
+        tokens.push(Token::SeqEnd);
+        tokens
+    }
+
+    #[test]
+    fn test_ser_de_strict_interval_set() {
+        let intervals = [(-10, -5), (3, 5), (20, 21)];
+        assert_tokens(
+            &StrictIntervalSet(intervals.to_interval_set()),
+            &interval_tokens(&intervals),
+        );
+        assert_tokens(
+            &StrictIntervalSet(IntervalSet::<i32>::empty()),
+            &[Token::None],
+        );
+    }
+
+    #[test]
+    fn test_de_strict_interval_set_errors() {
+        assert_de_tokens_error::<StrictIntervalSet<i32>>(
+            &interval_tokens(&[(3, 5), (-10, -5)]),
+            "Intervals #0 and #1 are not sorted by lower bound.",
+        );
+        assert_de_tokens_error::<StrictIntervalSet<i32>>(
+            &interval_tokens(&[(-10, -5), (3, 5), (4, 8)]),
+            "Intervals #1 and #2 are joinable and should have been merged.",
+        );
+        assert_de_tokens_error::<StrictIntervalSet<i32>>(
+            &interval_tokens(&[(3, 5), (6, 8)]),
+            "Intervals #0 and #1 are joinable and should have been merged.",
+        );
+        // The sizes of overlapping intervals covering the whole range would overflow the size of the set.
+        let mut tokens = vec![Token::Seq { len: Some(3) }];
+        for _ in 0..3 {
+            tokens.extend([
+                Token::Tuple { len: 2 },
+                Token::U8(0),
+                Token::U8(254),
+                Token::TupleEnd,
+            ]);
+        }
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens_error::<StrictIntervalSet<u8>>(
+            &tokens,
+            "Intervals #0 and #1 are not sorted by lower bound.",
+        );
+    }
 }
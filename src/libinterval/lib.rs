@@ -16,6 +16,10 @@
 //!
 //! Interval bounds must implement, for most operations, the `Width` trait. This is because the maximum size of an n-bits interval can not fit in an n-bits integer. Consider the interval `[0..1]` with 1-bit bounds, the size `2` can not be represented with only one bit. It needs `n+1` bits, and this is problematic with the largest primitive types such as `u64`. Therefore, the interval bounds must be used within the limits of `Width::min_value()` and `Width::max_value()`, and not by the limits provided by `num::traits::Bounded`.
 //!
+//! # Features
+//!
+//! The optional `rand` feature enables the random sampling of interval sets, such as `IntervalSet::sample_without_replacement`.
+//!
 //! # Examples
 //!
 //! For examples see the [interval module](interval/index.html), [interval set module](interval_set/index.html).
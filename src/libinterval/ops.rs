@@ -41,6 +41,19 @@ pub trait Width: Ord + Clone {
     fn width(lower: &Self, upper: &Self) -> Self::Output;
 }
 
+/// Rounding applied when a bound is divided, see [`IntervalSet::scale_round`](../interval_set/struct.IntervalSet.html#method.scale_round).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundMode {
+    /// Rounds towards negative infinity.
+    Floor,
+    /// Rounds towards positive infinity.
+    Ceil,
+    /// Rounds to the nearest integer, ties are rounded towards positive infinity.
+    Nearest,
+    /// Rounds the lower bounds towards negative infinity and the upper bounds towards positive infinity, so no value is lost.
+    Outward,
+}
+
 macro_rules! unsigned_width_impl
 {
   ( $( $t: ty ),* ) =>
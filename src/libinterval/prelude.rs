@@ -1,6 +1,7 @@
 //! The prelude imports all operations, traits and structs.
 pub use crate::interval::ToInterval;
 pub use crate::interval_set::ToIntervalSet;
+pub use crate::iset;
 pub use crate::ops::Range;
 pub use crate::ops::*;
 pub use crate::Interval;